@@ -0,0 +1,852 @@
+//! Procedural macros for the [`assert_enum_variants`] crate.
+//!
+//! This crate is not meant to be used directly. Instead, enable the corresponding
+//! feature of `assert_enum_variants` and use the macros it re-exports.
+//!
+//! [`assert_enum_variants`]: https://docs.rs/assert_enum_variants
+
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// An attribute for functions that asserts that the variants handled by a `match`
+/// with a wildcard arm, together with the explicitly ignored ones, are exactly the
+/// variants of the enum.
+///
+/// The attribute takes the path to the enum, optionally followed by
+/// `ignore(...)` with the variants that are deliberately left to the wildcard arm.
+/// Every `match` in the function that has a `_` arm and refers to variants of the
+/// enum is turned into an invocation of `assert_enum_variants!` with the variants
+/// referred to by its other arms, both in the patterns and in the bodies. A `match`
+/// nested in the arms of such a `match` is not asserted on its own, as its variants
+/// count as handled by the outer one.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::exhaustive_with_fallback;
+///
+/// #[allow(dead_code)]
+/// enum ResumeFileFormat {
+///     Pdf,
+///     Docx,
+///     Doc,
+///     Odt,
+/// }
+///
+/// impl ResumeFileFormat {
+///     #[exhaustive_with_fallback(ResumeFileFormat, ignore(Odt))]
+///     fn from_extension(ext: &str) -> Option<Self> {
+///         use ResumeFileFormat::{Doc, Docx, Pdf};
+///
+///         let file_format: ResumeFileFormat = match ext {
+///             "pdf" => Pdf,
+///             "docx" => Docx,
+///             "doc" => Doc,
+///             _ => return None,
+///         };
+///
+///         Some(file_format)
+///     }
+/// }
+/// ```
+///
+/// # Example of failure due to an unhandled variant
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::exhaustive_with_fallback;
+///
+/// #[allow(dead_code)]
+/// enum ResumeFileFormat {
+///     Pdf,
+///     Docx,
+///     Doc,
+///     Json,
+/// }
+///
+/// impl ResumeFileFormat {
+///     // This will fail to compile
+///     // because the `Json` variant is neither handled nor ignored.
+///     #[exhaustive_with_fallback(ResumeFileFormat)]
+///     fn from_extension(ext: &str) -> Option<Self> {
+///         let file_format = match ext {
+///             "pdf" => ResumeFileFormat::Pdf,
+///             "docx" => ResumeFileFormat::Docx,
+///             "doc" => ResumeFileFormat::Doc,
+///             _ => return None,
+///         };
+///
+///         Some(file_format)
+///     }
+/// }
+/// ```
+///
+/// # Nested `match` expressions
+///
+/// ```rust
+/// use assert_enum_variants::exhaustive_with_fallback;
+///
+/// #[allow(dead_code)]
+/// enum ResumeFileFormat {
+///     Pdf,
+///     Docx,
+///     Doc,
+///     Odt,
+/// }
+///
+/// impl ResumeFileFormat {
+///     #[exhaustive_with_fallback(ResumeFileFormat, ignore(Odt))]
+///     fn from_extension(ext: &str, legacy: bool) -> Option<Self> {
+///         let file_format = match ext {
+///             "pdf" => ResumeFileFormat::Pdf,
+///             "doc" | "docx" => match (ext, legacy) {
+///                 ("doc", _) | (_, true) => ResumeFileFormat::Doc,
+///                 _ => ResumeFileFormat::Docx,
+///             }
+///             _ => return None,
+///         };
+///
+///         Some(file_format)
+///     }
+/// }
+/// ```
+///
+/// # Recognized variants
+///
+/// Since the attribute only sees the tokens of the function, variants are recognized
+/// syntactically. A variant is either
+///
+/// * a path ending in `Enum::Variant`, where `Enum` is the last segment of the path
+///   passed to the attribute and `Variant` starts with an uppercase letter and, unless
+///   it's a single letter, contains a lowercase one, or
+/// * a bare name imported with a `use` declaration inside the function, either as
+///   `use Enum::Variant;` or as `use Enum::{Variant, ...};`.
+///
+/// Paths to `SCREAMING_CASE` associated constants are thus not taken for variants.
+///
+/// ```rust
+/// use assert_enum_variants::exhaustive_with_fallback;
+///
+/// #[allow(dead_code)]
+/// enum ResumeFileFormat {
+///     Pdf,
+///     Docx,
+/// }
+///
+/// impl ResumeFileFormat {
+///     const DEFAULT: Self = ResumeFileFormat::Pdf;
+///
+///     #[exhaustive_with_fallback(ResumeFileFormat)]
+///     fn from_extension(ext: &str) -> Option<Self> {
+///         let file_format = match ext {
+///             "" => ResumeFileFormat::DEFAULT,
+///             "pdf" => ResumeFileFormat::Pdf,
+///             "docx" => ResumeFileFormat::Docx,
+///             _ => return None,
+///         };
+///
+///         Some(file_format)
+///     }
+/// }
+/// ```
+///
+/// Glob imports of the variants are not supported. When the attribute is given
+/// `Self`, variants are recognized as `Self::Variant`. The generated code refers to
+/// the `assert_enum_variants` crate by its name, so it has to be a dependency of the
+/// crate using the attribute.
+#[proc_macro_attribute]
+pub fn exhaustive_with_fallback(args: TokenStream, item: TokenStream) -> TokenStream {
+    match expand_exhaustive_with_fallback(args, item.clone()) {
+        Ok(expanded) => expanded,
+        Err(error) => {
+            let mut expanded = error.into_compile_error();
+            expanded.extend(item);
+            expanded
+        }
+    }
+}
+
+/// A derive macro implementing the `EnumVariants` trait, which exposes the number,
+/// the names and the shapes of the variants of an enum.
+///
+/// Variants gated behind `#[cfg(...)]` attributes are only listed when they are
+/// compiled.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::{EnumVariants, VariantKind};
+///
+/// #[allow(dead_code)]
+/// #[derive(EnumVariants)]
+/// enum Message<'a, T: Clone> {
+///     Quit,
+///     Write(&'a str),
+///     Move { x: T, y: T },
+///     #[cfg(any())]
+///     Never,
+/// }
+///
+/// assert_eq!(Message::<'static, u8>::COUNT, 3);
+/// assert_eq!(Message::<'static, u8>::NAMES, ["Quit", "Write", "Move"]);
+/// assert_eq!(
+///     Message::<'static, u8>::KINDS,
+///     [VariantKind::Unit, VariantKind::Tuple, VariantKind::Struct],
+/// );
+/// ```
+///
+/// # Example of failure due to a struct
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::EnumVariants;
+///
+/// // This will fail to compile because `Point` is not an enum.
+/// #[derive(EnumVariants)]
+/// struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+/// ```
+///
+/// # Example of failure of the `derived` mode
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::{assert_enum_variants, EnumVariants};
+///
+/// #[allow(dead_code)]
+/// #[derive(EnumVariants)]
+/// pub enum Level {
+///     Debug,
+///     Info,
+///     Warn,
+/// }
+///
+/// // This will fail to compile with "the variant list doesn't match the enum;
+/// // missing: `Info`, `Warn`; unexpected: `Error`".
+/// assert_enum_variants!(Level, derived { Debug, Error });
+/// ```
+///
+/// # Example of failure of `assert_enums_mirror!`
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enums_mirror;
+///
+/// mod api {
+///     use assert_enum_variants::EnumVariants;
+///
+///     #[allow(dead_code)]
+///     #[derive(EnumVariants)]
+///     pub enum Status {
+///         Active,
+///         Suspended,
+///         Deleted,
+///     }
+/// }
+///
+/// mod db {
+///     use assert_enum_variants::EnumVariants;
+///
+///     #[allow(dead_code)]
+///     #[derive(EnumVariants)]
+///     pub enum Status {
+///         Active,
+///         Suspended,
+///     }
+/// }
+///
+/// // This will fail to compile with "the variants of `db::Status` don't mirror those
+/// // of `api::Status`; missing: `Deleted`".
+/// assert_enums_mirror!(api::Status, db::Status);
+/// ```
+#[proc_macro_derive(EnumVariants)]
+pub fn derive_enum_variants(input: TokenStream) -> TokenStream {
+    match expand_enum_variants(input) {
+        Ok(expanded) => expanded,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+struct Error {
+    message: String,
+    span: Span,
+}
+
+impl Error {
+    fn new(span: Span, message: impl Into<String>) -> Self {
+        Error {
+            message: message.into(),
+            span,
+        }
+    }
+
+    fn into_compile_error(self) -> TokenStream {
+        let message = format!("::core::compile_error! {{ {:?} }}", self.message);
+        respan(message.parse().unwrap(), self.span)
+    }
+}
+
+fn respan(stream: TokenStream, span: Span) -> TokenStream {
+    stream
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = TokenTree::Group(respanned);
+            }
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
+struct Args {
+    path: Vec<TokenTree>,
+    name: String,
+    ignored: Vec<Ident>,
+}
+
+struct Arm {
+    pattern: Vec<TokenTree>,
+    rest: Vec<TokenTree>,
+}
+
+struct Match {
+    arms: Vec<Arm>,
+    nested: Vec<Match>,
+}
+
+fn expand_exhaustive_with_fallback(
+    args: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream, Error> {
+    let args = parse_args(args)?;
+
+    let mut item: Vec<TokenTree> = item.into_iter().collect();
+    let is_fn = item
+        .iter()
+        .any(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "fn"));
+    let body = match item.last() {
+        Some(TokenTree::Group(group)) if is_fn && group.delimiter() == Delimiter::Brace => {
+            group.clone()
+        }
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`#[exhaustive_with_fallback]` can only be applied to functions",
+            ));
+        }
+    };
+
+    let body_tokens: Vec<TokenTree> = body.stream().into_iter().collect();
+    let mut imports = Vec::new();
+    collect_imports(&body_tokens, &args.name, &mut imports);
+    let mut matches = Vec::new();
+    collect_matches(&body_tokens, &mut matches);
+
+    let mut assertions = TokenStream::new();
+    collect_assertions(&matches, &args, &imports, &mut assertions)?;
+
+    if assertions.is_empty() {
+        return Err(Error::new(
+            Span::call_site(),
+            format!(
+                "expected a `match` with a wildcard arm handling variants of `{}`",
+                args.name,
+            ),
+        ));
+    }
+
+    assertions.extend(body.stream());
+    let mut expanded_body = Group::new(Delimiter::Brace, assertions);
+    expanded_body.set_span(body.span());
+    item.pop();
+    item.push(TokenTree::Group(expanded_body));
+    Ok(item.into_iter().collect())
+}
+
+/// Asserts every outermost `match` with a wildcard arm. The `match` expressions nested
+/// in its arms are left alone, since their variants are handled by it and `ignore`
+/// applies to all of them alike.
+fn collect_assertions(
+    matches: &[Match],
+    args: &Args,
+    imports: &[(String, Ident)],
+    assertions: &mut TokenStream,
+) -> Result<(), Error> {
+    for Match { arms, nested } in matches {
+        if !arms.iter().any(is_wildcard) {
+            collect_assertions(nested, args, imports, assertions)?;
+            continue;
+        }
+
+        let mut handled = Vec::new();
+        for arm in arms.iter().filter(|arm| !is_wildcard(arm)) {
+            collect_variants(&arm.pattern, &args.name, imports, &mut handled);
+            collect_variants(&arm.rest, &args.name, imports, &mut handled);
+        }
+        if handled.is_empty() {
+            collect_assertions(nested, args, imports, assertions)?;
+            continue;
+        }
+
+        let mut unique: Vec<Ident> = Vec::new();
+        for variant in handled {
+            if !unique
+                .iter()
+                .any(|seen| seen.to_string() == variant.to_string())
+            {
+                unique.push(variant);
+            }
+        }
+        if let Some(ignored) = args
+            .ignored
+            .iter()
+            .find(|ignored| unique.iter().any(|v| v.to_string() == ignored.to_string()))
+        {
+            return Err(Error::new(
+                ignored.span(),
+                format!("`{ignored}` is handled by the `match`, so it can't be ignored"),
+            ));
+        }
+
+        assertions.extend(assertion(args, unique));
+    }
+    Ok(())
+}
+
+fn parse_args(args: TokenStream) -> Result<Args, Error> {
+    let mut segments = vec![Vec::new()];
+    for token in args {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => segments.push(Vec::new()),
+            _ => segments.last_mut().unwrap().push(token),
+        }
+    }
+    if segments.last().is_some_and(Vec::is_empty) && segments.len() > 1 {
+        segments.pop();
+    }
+
+    let mut segments = segments.into_iter();
+    let path = segments.next().unwrap();
+    let name = match path.last() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "expected the path to the enum, e.g. `#[exhaustive_with_fallback(MyEnum)]`",
+            ));
+        }
+    };
+
+    let mut ignored = Vec::new();
+    for segment in segments {
+        match &segment[..] {
+            [TokenTree::Ident(keyword), TokenTree::Group(group)]
+                if keyword.to_string() == "ignore"
+                    && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                for token in group.stream() {
+                    match token {
+                        TokenTree::Ident(variant) => ignored.push(variant),
+                        TokenTree::Punct(punct) if punct.as_char() == ',' => {}
+                        other => {
+                            return Err(Error::new(other.span(), "expected a variant name"));
+                        }
+                    }
+                }
+            }
+            _ => {
+                let span = segment
+                    .first()
+                    .map_or_else(Span::call_site, TokenTree::span);
+                return Err(Error::new(span, "expected `ignore(...)`"));
+            }
+        }
+    }
+
+    Ok(Args {
+        path,
+        name,
+        ignored,
+    })
+}
+
+fn assertion(args: &Args, variants: Vec<Ident>) -> TokenStream {
+    let mut list = TokenStream::new();
+    for variant in variants.into_iter().chain(args.ignored.iter().cloned()) {
+        list.extend([
+            TokenTree::Ident(variant),
+            TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+        ]);
+    }
+
+    let mut input: TokenStream = args.path.iter().cloned().collect();
+    input.extend([
+        TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+        TokenTree::Group(Group::new(Delimiter::Brace, list)),
+    ]);
+
+    let mut assertion: TokenStream = "::assert_enum_variants::assert_enum_variants!"
+        .parse()
+        .unwrap();
+    assertion.extend([
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, input)),
+        TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+    ]);
+    assertion
+}
+
+fn is_punct(token: Option<&TokenTree>, ch: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == ch)
+}
+
+fn is_ident(token: Option<&TokenTree>, name: &str) -> bool {
+    matches!(token, Some(TokenTree::Ident(ident)) if ident.to_string() == name)
+}
+
+fn is_brace(token: Option<&TokenTree>) -> bool {
+    matches!(token, Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace)
+}
+
+fn is_path_sep(tokens: &[TokenTree], i: usize) -> bool {
+    is_punct(tokens.get(i), ':') && is_punct(tokens.get(i + 1), ':')
+}
+
+fn is_wildcard(arm: &Arm) -> bool {
+    matches!(&arm.pattern[..], [TokenTree::Ident(ident)] if ident.to_string() == "_")
+}
+
+/// Collects the local names of the variants imported by `use` declarations, along
+/// with the variants they refer to.
+fn collect_imports(tokens: &[TokenTree], name: &str, imports: &mut Vec<(String, Ident)>) {
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if ident.to_string() == "use" => {
+                let end = tokens[i..]
+                    .iter()
+                    .position(|token| is_punct(Some(token), ';'))
+                    .map_or(tokens.len(), |end| i + end);
+                let declaration = &tokens[i..end];
+                let Some(j) = (0..declaration.len()).find(|&j| {
+                    matches!(&declaration[j], TokenTree::Ident(ident) if ident.to_string() == name)
+                        && is_path_sep(declaration, j + 1)
+                }) else {
+                    continue;
+                };
+                match &declaration[j + 3..] {
+                    [TokenTree::Group(group)] if group.delimiter() == Delimiter::Brace => {
+                        let items: Vec<TokenTree> = group.stream().into_iter().collect();
+                        for item in items.split(|token| is_punct(Some(token), ',')) {
+                            collect_import(item, imports);
+                        }
+                    }
+                    item => collect_import(item, imports),
+                }
+            }
+            TokenTree::Group(group) => {
+                let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+                collect_imports(&tokens, name, imports);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_import(item: &[TokenTree], imports: &mut Vec<(String, Ident)>) {
+    match item {
+        [TokenTree::Ident(variant)] => imports.push((variant.to_string(), variant.clone())),
+        [
+            TokenTree::Ident(variant),
+            TokenTree::Ident(keyword),
+            TokenTree::Ident(alias),
+        ] if keyword.to_string() == "as" => {
+            imports.push((alias.to_string(), variant.clone()));
+        }
+        _ => {}
+    }
+}
+
+/// Collects every `match` expression, along with the ones nested in its arms.
+fn collect_matches(tokens: &[TokenTree], matches: &mut Vec<Match>) {
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            TokenTree::Ident(ident) if ident.to_string() == "match" => {
+                // Struct literals are not allowed in the scrutinee, so the first
+                // brace-delimited group is the body of the `match`.
+                if let Some(body) = (i + 1..tokens.len()).find(|&j| is_brace(tokens.get(j))) {
+                    collect_matches(&tokens[i + 1..body], matches);
+                    let tokens: Vec<TokenTree> = match &tokens[body] {
+                        TokenTree::Group(group) => group.stream().into_iter().collect(),
+                        _ => unreachable!(),
+                    };
+                    let mut nested = Vec::new();
+                    collect_matches(&tokens, &mut nested);
+                    matches.push(Match {
+                        arms: split_arms(&tokens),
+                        nested,
+                    });
+                    i = body;
+                }
+            }
+            TokenTree::Group(group) => {
+                let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+                collect_matches(&tokens, matches);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+fn split_arms(tokens: &[TokenTree]) -> Vec<Arm> {
+    let mut arms = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let mut pattern = Vec::new();
+        while i < tokens.len()
+            && !(is_punct(tokens.get(i), '=') && is_punct(tokens.get(i + 1), '>'))
+        {
+            pattern.push(tokens[i].clone());
+            i += 1;
+        }
+        i += 2;
+
+        // A guard is not part of the pattern, but it may refer to variants all the
+        // same, so it's kept along with the body.
+        let guard = pattern.iter().position(
+            |token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "if"),
+        );
+        let mut rest = guard.map_or_else(Vec::new, |guard| pattern.split_off(guard));
+
+        // Like in a statement, a block-like body needs no comma after it, unless it's
+        // the receiver of a method call or of `?`.
+        let end = match block_like_end(tokens, i) {
+            Some(end) if !is_punct(tokens.get(end), '.') && !is_punct(tokens.get(end), '?') => end,
+            _ => (i..tokens.len())
+                .find(|&j| is_punct(tokens.get(j), ','))
+                .unwrap_or(tokens.len()),
+        };
+        rest.extend(tokens[i.min(end)..end].iter().cloned());
+        i = end;
+        if is_punct(tokens.get(i), ',') {
+            i += 1;
+        }
+
+        arms.push(Arm { pattern, rest });
+    }
+    arms
+}
+
+/// Returns the end of the block-like expression starting at `i`, if there is one.
+fn block_like_end(tokens: &[TokenTree], i: usize) -> Option<usize> {
+    let after_block = |from: usize| {
+        (from..tokens.len())
+            .find(|&j| is_brace(tokens.get(j)))
+            .map(|j| j + 1)
+    };
+    match tokens.get(i)? {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => Some(i + 1),
+        TokenTree::Ident(ident) => match ident.to_string().as_str() {
+            "loop" | "unsafe" | "match" | "while" | "for" => after_block(i + 1),
+            "if" => {
+                let mut end = after_block(i + 1)?;
+                while is_ident(tokens.get(end), "else") {
+                    end = after_block(end + 1)?;
+                }
+                Some(end)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn collect_variants(
+    tokens: &[TokenTree],
+    name: &str,
+    imports: &[(String, Ident)],
+    variants: &mut Vec<Ident>,
+) {
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if ident.to_string() == name && is_path_sep(tokens, i + 1) => {
+                if let Some(TokenTree::Ident(variant)) = tokens.get(i + 3) {
+                    // Associated constants such as `Enum::DEFAULT` are told apart from
+                    // variants by their case.
+                    let variant_name = variant.to_string();
+                    let is_variant = variant_name.starts_with(char::is_uppercase)
+                        && (variant_name.len() == 1 || variant_name.contains(char::is_lowercase));
+                    if is_variant && !is_path_sep(tokens, i + 4) {
+                        variants.push(variant.clone());
+                    }
+                }
+            }
+            TokenTree::Ident(ident) => {
+                let is_path_segment =
+                    (i >= 2 && is_path_sep(tokens, i - 2)) || is_path_sep(tokens, i + 1);
+                let import = imports
+                    .iter()
+                    .find(|(local, _)| *local == ident.to_string());
+                if let (false, Some((_, variant))) = (is_path_segment, import) {
+                    let mut variant = variant.clone();
+                    variant.set_span(ident.span());
+                    variants.push(variant);
+                }
+            }
+            TokenTree::Group(group) => {
+                let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+                collect_variants(&tokens, name, imports, variants);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn expand_enum_variants(input: TokenStream) -> Result<TokenStream, Error> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let keyword = tokens
+        .iter()
+        .position(|token| {
+            matches!(
+                token,
+                TokenTree::Ident(ident) if ["enum", "struct", "union"].contains(&&*ident.to_string())
+            )
+        })
+        .ok_or_else(|| Error::new(Span::call_site(), "expected an enum"))?;
+    if tokens[keyword].to_string() != "enum" {
+        return Err(Error::new(
+            tokens[keyword].span(),
+            "`EnumVariants` can only be derived for enums",
+        ));
+    }
+    let Some(TokenTree::Ident(name)) = tokens.get(keyword + 1) else {
+        return Err(Error::new(
+            tokens[keyword].span(),
+            "expected the name of the enum",
+        ));
+    };
+    let Some(TokenTree::Group(body)) = tokens.last() else {
+        return Err(Error::new(name.span(), "expected the variants of the enum"));
+    };
+
+    let mut i = keyword + 2;
+    let mut generics = Vec::new();
+    if is_punct(tokens.get(i), '<') {
+        let mut depth = 0;
+        loop {
+            match &tokens[i] {
+                TokenTree::Punct(punct) if punct.as_char() == '<' => depth += 1,
+                // `->` in `Fn` bounds doesn't close the generics.
+                TokenTree::Punct(punct)
+                    if punct.as_char() == '>' && !is_punct(generics.last(), '-') =>
+                {
+                    depth -= 1
+                }
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+            generics.push(tokens[i].clone());
+            i += 1;
+        }
+        generics.remove(0);
+        i += 1;
+    }
+    let where_clause: TokenStream = tokens[i..tokens.len() - 1].iter().cloned().collect();
+
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    for param in split_top_level(&generics, ',') {
+        if param.is_empty() {
+            continue;
+        }
+        // Defaults are not allowed on the parameters of an `impl` block.
+        params.push(
+            split_top_level(param, '=')[0]
+                .iter()
+                .cloned()
+                .collect::<TokenStream>()
+                .to_string(),
+        );
+        let arg = match param {
+            [TokenTree::Punct(quote), lifetime, ..] if quote.as_char() == '\'' => {
+                format!("'{lifetime}")
+            }
+            [TokenTree::Ident(keyword), arg, ..] if keyword.to_string() == "const" => {
+                arg.to_string()
+            }
+            [arg, ..] => arg.to_string(),
+            [] => unreachable!(),
+        };
+        args.push(arg);
+    }
+
+    let mut names = String::new();
+    let mut kinds = String::new();
+    let variants: Vec<TokenTree> = body.stream().into_iter().collect();
+    for variant in variants.split(|token| is_punct(Some(token), ',')) {
+        let mut j = 0;
+        let mut cfgs = String::new();
+        while is_punct(variant.get(j), '#') {
+            if let Some(TokenTree::Group(attr)) = variant.get(j + 1)
+                && attr
+                    .stream()
+                    .into_iter()
+                    .next()
+                    .is_some_and(|token| token.to_string() == "cfg")
+            {
+                cfgs.push_str(&format!("#[{}] ", attr.stream()));
+            }
+            j += 2;
+        }
+        let Some(TokenTree::Ident(variant_name)) = variant.get(j) else {
+            continue;
+        };
+        let kind = match variant.get(j + 1) {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => "Tuple",
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => "Struct",
+            _ => "Unit",
+        };
+        let variant_name = variant_name.to_string();
+        let variant_name = variant_name.trim_start_matches("r#");
+        names.push_str(&format!("{cfgs}{variant_name:?}, "));
+        kinds.push_str(&format!(
+            "{cfgs}::assert_enum_variants::VariantKind::{kind}, "
+        ));
+    }
+
+    let expanded = format!(
+        "impl<{params}> ::assert_enum_variants::EnumVariants for {name}<{args}> {where_clause} {{
+            const NAMES: &'static [&'static str] = &[{names}];
+            const KINDS: &'static [::assert_enum_variants::VariantKind] = &[{kinds}];
+        }}",
+        params = params.join(", "),
+        args = args.join(", "),
+    );
+    Ok(expanded.parse().unwrap())
+}
+
+/// Splits generic parameters at the separators that are not nested in angle brackets.
+fn split_top_level(tokens: &[TokenTree], separator: char) -> Vec<&[TokenTree]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '<' => depth += 1,
+            TokenTree::Punct(punct)
+                if punct.as_char() == '>' && !(i > 0 && is_punct(tokens.get(i - 1), '-')) =>
+            {
+                depth -= 1
+            }
+            TokenTree::Punct(punct) if punct.as_char() == separator && depth == 0 => {
+                parts.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
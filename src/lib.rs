@@ -1,6 +1,15 @@
 #![no_std]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "derive")]
+pub use assert_enum_variants_macros::EnumVariants;
+#[cfg(feature = "attr")]
+pub use assert_enum_variants_macros::exhaustive_with_fallback;
+
+// Lets the tests use the derive, whose expansion refers to the crate by its name.
+#[cfg(test)]
+extern crate self as assert_enum_variants;
+
 /// This macro performs a compile-time check to validate that all variants of an enum
 /// are as provided in the macro invocation.
 ///
@@ -67,6 +76,404 @@
 /// assert_enum_variants!(MyEnum, { A, B, C, D });
 /// ```
 ///
+/// # Variant shapes
+///
+/// By default, a listed variant may be of any shape. Each entry of the list can
+/// additionally assert the shape of the variant:
+///
+/// * `unit A` asserts that `A` is a unit variant,
+/// * `B(..)` asserts that `B` is a tuple variant,
+/// * `C { .. }` asserts that `C` is a struct variant.
+///
+/// The fields of a struct variant can be listed as well. `C { a, b }` asserts that
+/// `C` has exactly the fields `a` and `b`, while `C { a, .. }` only asserts that `C`
+/// has *at least* the field `a`.
+///
+/// Similarly, the number of fields of a tuple variant can be pinned with
+/// underscores. `B(_, _)` asserts that `B` has exactly two fields, while `B(_, ..)`
+/// only asserts that it has *at least* one.
+///
+/// Finally, the underscores and the field names can be accompanied by types, e.g.
+/// `B(u32)` or `C { a: String, b: u32 }`, to assert the types of the payload as well.
+/// Types that don't matter can be left out or replaced with `_`.
+///
+/// ```rust
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum MyEnum {
+///     A,
+///     B(u32),
+///     C {
+///         a: String,
+///         b: u32,
+///     },
+/// }
+///
+/// assert_enum_variants!(MyEnum, { unit A, B(..), C { .. } });
+/// assert_enum_variants!(MyEnum, { A, B, C { a, b } });
+/// assert_enum_variants!(MyEnum, { A, B, C { b, .. } });
+/// assert_enum_variants!(MyEnum, { A, B(_), C });
+/// assert_enum_variants!(MyEnum, { unit A, B(u32), C { a: String, b: u32 } });
+/// assert_enum_variants!(MyEnum, { A, B(_), C { a: String, b } });
+/// ```
+///
+/// # Example of failure due to a changed shape
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum MyEnum {
+///     A,
+///     B { x: u32 },
+///     C {
+///         a: String,
+///         b: u32,
+///     },
+/// }
+///
+/// // This will fail to compile
+/// // because `B` is a struct variant rather than a tuple variant.
+/// assert_enum_variants!(MyEnum, { unit A, B(..), C { .. } });
+/// ```
+///
+/// # Example of failure due to an added field
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum MyEnum {
+///     A,
+///     B(u32),
+///     C {
+///         a: String,
+///         b: u32,
+///         c: bool,
+///     },
+/// }
+///
+/// // This will fail to compile
+/// // because the `c` field of `C` is not listed.
+/// assert_enum_variants!(MyEnum, { A, B, C { a, b } });
+/// ```
+///
+/// # Example of failure due to a changed number of fields
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum MyEnum {
+///     A,
+///     B(u32, u32),
+///     C {
+///         a: String,
+///         b: u32,
+///     },
+/// }
+///
+/// // This will fail to compile
+/// // because `B` has two fields rather than one.
+/// assert_enum_variants!(MyEnum, { A, B(_), C });
+/// ```
+///
+/// # Example of failure due to a changed payload type
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum MyEnum {
+///     A,
+///     B(u64),
+///     C {
+///         a: String,
+///         b: u32,
+///     },
+/// }
+///
+/// // This will fail to compile
+/// // because the field of `B` is of type `u64` rather than `u32`.
+/// assert_enum_variants!(MyEnum, { A, B(u32), C { a: String, b: u32 } });
+/// ```
+///
+/// Payload types are compared exactly, so a listed type that the field would merely
+/// coerce to isn't accepted either.
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum MyEnum {
+///     A,
+///     B(&'static mut u32),
+/// }
+///
+/// // This will fail to compile
+/// // because the field of `B` is of type `&'static mut u32` rather than `&'static u32`.
+/// assert_enum_variants!(MyEnum, { A, B(&'static u32) });
+/// ```
+///
+/// # Discriminants
+///
+/// For fieldless enums, a variant can be followed by `= <discriminant>` to assert
+/// its discriminant as well. The discriminant can be any constant expression.
+///
+/// ```rust
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// #[repr(u16)]
+/// pub enum Code {
+///     Ok = 0,
+///     NotFound = 404,
+///     Internal = 500,
+/// }
+///
+/// assert_enum_variants!(Code, { Ok = 0, NotFound = 404, Internal = 500 });
+/// ```
+///
+/// # Example of failure due to a changed discriminant
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// #[repr(u16)]
+/// pub enum Code {
+///     Ok = 0,
+///     NotFound = 404,
+///     Internal = 501,
+/// }
+///
+/// // This will fail to compile
+/// // because the discriminant of `Internal` is 501 rather than 500.
+/// assert_enum_variants!(Code, { Ok = 0, NotFound = 404, Internal = 500 });
+/// ```
+///
+/// # Declaration order
+///
+/// Prefixing the variant list with `ordered` additionally asserts that the variants
+/// are declared in the listed order, which matters for implicit discriminants,
+/// derived [`PartialOrd`] and [`Ord`] implementations, and formats that encode
+/// variants by their index.
+///
+/// ```rust
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum Priority {
+///     Low,
+///     Medium,
+///     High,
+/// }
+///
+/// assert_enum_variants!(Priority, ordered { Low, Medium, High });
+/// ```
+///
+/// The declaration order can only be observed through the discriminants, so this
+/// mode asserts that the discriminant of every variant is its position in the list.
+/// This holds exactly when the variants are declared in the listed order, provided
+/// that the discriminants are implicit. Enums with explicit discriminants that differ
+/// from the positions of their variants are therefore rejected, even if they are
+/// declared in the listed order. Enums with payloads are not supported.
+///
+/// # Example of failure due to reordered variants
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum Priority {
+///     Low,
+///     High,
+///     Medium,
+/// }
+///
+/// // This will fail to compile
+/// // because `High` is declared before `Medium`.
+/// assert_enum_variants!(Priority, ordered { Low, Medium, High });
+/// ```
+///
+/// # Example of failure due to explicit discriminants
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// #[repr(u8)]
+/// pub enum Priority {
+///     Low = 2,
+///     High = 1,
+/// }
+///
+/// // This will fail to compile
+/// // because `Low` is declared before `High`, whatever their discriminants.
+/// assert_enum_variants!(Priority, ordered { High, Low });
+/// ```
+///
+/// # Generic enums
+///
+/// Generic enums can be checked by introducing their generic parameters with a
+/// `for<...>` prefix. Bounds on the parameters go into a `where` clause after the
+/// variant list.
+///
+/// ```rust
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// pub trait Id {}
+///
+/// #[allow(dead_code)]
+/// pub enum Event<'a, T: Id> {
+///     Created(T),
+///     Renamed { id: T, name: &'a str },
+///     Cleared,
+/// }
+///
+/// assert_enum_variants!(
+///     for<'a, T> Event<'a, T>,
+///     { Created(T), Renamed { id: T, name: &'a str }, unit Cleared }
+///     where T: Id
+/// );
+/// ```
+///
+/// The generic arguments of the enum type must be single tokens, such as the
+/// introduced parameters themselves. Discriminants and the `ordered` mode are not
+/// available for generic enums since these require fieldless enums.
+///
+/// # Use inside `impl` blocks
+///
+/// Inside an `impl` block of the enum, the enum can be referred to as `Self`. In
+/// this case, the macro expands to an expression of type `()` rather than to an
+/// item, so it can be used in function bodies and in the initializers of
+/// associated constants, with the generics of the `impl` block in scope.
+///
+/// ```rust
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum Tree<T> {
+///     Leaf(T),
+///     Node { left: Box<Tree<T>>, right: Box<Tree<T>> },
+/// }
+///
+/// impl<T> Tree<T> {
+///     const VARIANTS: () = assert_enum_variants!(Self, { Leaf(T), Node { left, right } });
+///
+///     pub fn depth(&self) -> usize {
+///         assert_enum_variants!(Self, { Leaf, Node });
+///
+///         match self {
+///             Self::Leaf(_) => 1,
+///             Self::Node { left, right } => 1 + left.depth().max(right.depth()),
+///         }
+///     }
+/// }
+/// ```
+///
+/// Since the variants can't be imported from `Self`, discriminants and the `ordered`
+/// mode are not available in this form, and `C { .. }` or `C {}` don't assert that
+/// `C` is a struct variant.
+///
+/// # Associated types
+///
+/// Enums reached through an associated type can be checked by spelling out the
+/// projection. A projection on a generic parameter needs a `for<...>` prefix and a
+/// `where` clause pinning the associated type to an enum.
+///
+/// ```rust
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// pub trait Codec {
+///     type Error;
+/// }
+///
+/// #[allow(dead_code)]
+/// pub enum JsonError {
+///     Syntax { line: usize },
+///     Eof,
+/// }
+///
+/// pub struct Json;
+///
+/// impl Codec for Json {
+///     type Error = JsonError;
+/// }
+///
+/// assert_enum_variants!(<Json as Codec>::Error, { Syntax { line }, Eof });
+/// assert_enum_variants!(
+///     for<C> <C as Codec>::Error,
+///     { Syntax { line: usize }, unit Eof }
+///     where C: Codec<Error = JsonError>
+/// );
+/// ```
+///
+/// The shorthand `T::Error` can be used in place of `<T as Codec>::Error` when the
+/// bounds on `T` leave no ambiguity, e.g. `for<T> T::Error`.
+///
+/// As with `Self`, the variants can't be imported through a projection, so
+/// `C { .. }` or `C {}` don't assert that `C` is a struct variant. Projections on
+/// generic parameters additionally don't support discriminants.
+///
+/// # Conditionally compiled variants
+///
+/// Entries accept outer attributes, which are applied to every check generated for
+/// them. This allows asserting enums whose variants are gated behind a feature or a
+/// target with a single invocation.
+///
+/// ```rust
+/// use assert_enum_variants::assert_enum_variants;
+///
+/// #[allow(dead_code)]
+/// pub enum Transport {
+///     Tcp,
+///     #[cfg(unix)]
+///     Unix,
+///     #[cfg(feature = "tls")]
+///     Tls,
+/// }
+///
+/// assert_enum_variants!(Transport, {
+///     Tcp,
+///     #[cfg(unix)]
+///     Unix,
+///     #[cfg(feature = "tls")]
+///     Tls,
+/// });
+/// ```
+///
+/// # Precise diagnostics
+///
+/// The errors reported for a mismatching list come from the compiler's checks of the
+/// generated code, so they mention one variant at a time. For enums implementing
+/// [`EnumVariants`], e.g. through `#[derive(EnumVariants)]` with the `derive` feature,
+/// prefixing the variant list with `derived` additionally compares the listed names
+/// with [`EnumVariants::NAMES`] and reports all of the missing and unexpected
+/// variants at once.
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use assert_enum_variants::{assert_enum_variants, EnumVariants};
+///
+/// #[allow(dead_code)]
+/// #[derive(EnumVariants)]
+/// pub enum Level {
+///     Debug,
+///     Info,
+/// }
+///
+/// assert_enum_variants!(Level, derived { Debug, Info });
+/// # }
+/// ```
+///
+/// If a `Warn` variant is added to `Level` and the list becomes `{ Debug, Error }`,
+/// the invocation fails to compile with "the variant list doesn't match the enum;
+/// missing: `Info`, `Warn`; unexpected: `Error`". This failure is exercised in the
+/// documentation of the derive, which requires the `derive` feature.
+///
 /// # Reasons for using this macro
 ///
 /// Let's say you're writing some code that needs to handle all variants of an enum
@@ -137,43 +544,2267 @@
 /// ```
 #[macro_export]
 macro_rules! assert_enum_variants {
-    ($enum:path, { $($variant:ident),* $(,)? }) => {
+    (Self, {
+        $(
+            $(#[$attr:meta])* $head:ident $($variant:ident)?
+            $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )?
+        ),* $(,)?
+    }) => {{
+        // `Self` can't be named by an item nor imported from, so the checks are
+        // performed in place and the variants are referred to as `Self::Variant`.
+        $crate::__assert_enum_variants!(Self, [], [Self::], [], {
+            $(
+                $(#[$attr])* $head $($variant)?
+                $( ( $($tuple)* ) )? $( { $($fields)* } )?
+            ),*
+        });
+    }};
+    (<$qself:ty as $trait:path>::$assoc:ident, {
+        $(
+            $(#[$attr:meta])* $head:ident $($variant:ident)?
+            $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )? $( = $discriminant:expr )?
+        ),* $(,)?
+    }) => {
         const _: () = {
-            #[allow(unreachable_code)]
-            if false {
-                #[allow(clippy::diverging_sub_expression)]
-                let _unreachable_obj: $enum = core::unreachable!();
-
-                #[allow(unused_imports)]
-                use $enum::{ $($variant),* };
+            // Variants can't be imported through a projection, but they can be referred
+            // to through a type alias of it.
+            type Enum = <$qself as $trait>::$assoc;
 
-                match _unreachable_obj {
-                    $(
-                        $variant { .. } => (),
-                    )*
-                };
+            $crate::__assert_enum_variants!(Enum, [], [Enum::], [], {
+                $(
+                    $(#[$attr])* $head $($variant)?
+                    $( ( $($tuple)* ) )? $( { $($fields)* } )? $( = $discriminant )?
+                ),*
+            });
+        };
+    };
+    (for<$($param:ident),+, $($lifetime:lifetime),+> $($rest:tt)*) => {
+        $crate::assert_enum_variants!(for<$($lifetime),+, $($param),+> $($rest)*);
+    };
+    (
+        for<$($lifetime:lifetime),* $(,)? $($param:ident),*>
+        $qself:ident::$assoc:ident,
+        {
+            $(
+                $(#[$attr:meta])* $head:ident $($variant:ident)?
+                $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )?
+            ),* $(,)?
+        }
+        $(where $($bounds:tt)*)?
+    ) => {
+        const _: () = {
+            // `T::Assoc` is the shorthand of `<T as Trait>::Assoc`, so it's checked in
+            // the same way. A two-segment path to an enum is checked in this way too,
+            // as it has no generic arguments that the parameters could appear in.
+            #[allow(dead_code)]
+            fn assert_enum_variants<$($lifetime,)* $($param,)*>() $(where $($bounds)*)? {
+                $crate::__assert_enum_variants!(
+                    $qself::$assoc,
+                    [],
+                    [$qself::$assoc::],
+                    [],
+                    {
+                        $(
+                            $(#[$attr])* $head $($variant)?
+                            $( ( $($tuple)* ) )? $( { $($fields)* } )?
+                        ),*
+                    }
+                );
             }
         };
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    mod my_mod {
-        #[allow(dead_code)]
-        pub enum MyEnum {
-            A,
-            B(u32),
-            C { a: u64, b: u32 },
+    };
+    (
+        for<$($lifetime:lifetime),* $(,)? $($param:ident),*>
+        $($segment:ident)::+ $(<$($arg:tt),*>)?,
+        {
+            $(
+                $(#[$attr:meta])* $head:ident $($variant:ident)?
+                $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )?
+            ),* $(,)?
         }
-    }
+        $(where $($bounds:tt)*)?
+    ) => {
+        const _: () = {
+            // A constant can't be generic, but a function can, and its body is checked
+            // all the same even though it's never called. Taking the enum as a parameter
+            // brings in the outlives bounds implied by its type.
+            #[allow(dead_code)]
+            fn assert_enum_variants<$($lifetime,)* $($param,)*>(
+                _: $($segment)::+ $(<$($arg),*>)?,
+            ) $(where $($bounds)*)? {
+                $crate::__assert_enum_variants!(
+                    $($segment)::+ $(<$($arg),*>)?,
+                    [$($segment)::+],
+                    [],
+                    [],
+                    {
+                        $(
+                            $(#[$attr])* $head $($variant)?
+                            $( ( $($tuple)* ) )? $( { $($fields)* } )?
+                        ),*
+                    }
+                );
+            }
+        };
+    };
+    (
+        for<$($lifetime:lifetime),* $(,)? $($param:ident),*>
+        <$qself:ident as $trait:path>::$assoc:ident,
+        {
+            $(
+                $(#[$attr:meta])* $head:ident $($variant:ident)?
+                $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )?
+            ),* $(,)?
+        }
+        $(where $($bounds:tt)*)?
+    ) => {
+        const _: () = {
+            // Neither an import nor a type alias can refer to a projection on a generic
+            // parameter, so the variants are referred to as `T::Assoc::Variant`, which
+            // resolves as long as the bounds pin the associated type to an enum.
+            #[allow(dead_code)]
+            fn assert_enum_variants<$($lifetime,)* $($param,)*>() $(where $($bounds)*)? {
+                $crate::__assert_enum_variants!(
+                    <$qself as $trait>::$assoc,
+                    [],
+                    [$qself::$assoc::],
+                    [],
+                    {
+                        $(
+                            $(#[$attr])* $head $($variant)?
+                            $( ( $($tuple)* ) )? $( { $($fields)* } )?
+                        ),*
+                    }
+                );
+            }
+        };
+    };
+    ($enum:path, {
+        $(
+            $(#[$attr:meta])* $head:ident $($variant:ident)?
+            $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )? $( = $discriminant:expr )?
+        ),* $(,)?
+    }) => {
+        const _: () = {
+            $crate::__assert_enum_variants!($enum, [$enum], [], [], {
+                $(
+                    $(#[$attr])* $head $($variant)?
+                    $( ( $($tuple)* ) )? $( { $($fields)* } )? $( = $discriminant )?
+                ),*
+            });
+        };
+    };
+    ($enum:path, derived {
+        $(
+            $(#[$attr:meta])* $head:ident $($variant:ident)?
+            $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )? $( = $discriminant:expr )?
+        ),* $(,)?
+    }) => {
+        // Evaluated separately from the rest of the checks, so that the summary is
+        // reported alongside the errors for the individual variants.
+        const _: () = $crate::__private::assert_same_names(
+            "the variant list doesn't match the enum",
+            <$enum as $crate::EnumVariants>::NAMES,
+            &[
+                $(
+                    $(#[$attr])*
+                    $crate::__enum_variant_entry!(@name $head $($variant)?)
+                ),*
+            ],
+        );
 
-    #[allow(dead_code)]
-    enum Never {}
+        $crate::assert_enum_variants!($enum, {
+            $(
+                $(#[$attr])* $head $($variant)?
+                $( ( $($tuple)* ) )? $( { $($fields)* } )? $( = $discriminant )?
+            ),*
+        });
+    };
+    ($enum:path, ordered {
+        $(
+            $(#[$attr:meta])* $head:ident $($variant:ident)?
+            $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )? $( = $discriminant:expr )?
+        ),* $(,)?
+    }) => {
+        $crate::assert_enum_variants!($enum, {
+            $(
+                $(#[$attr])* $head $($variant)?
+                $( ( $($tuple)* ) )? $( { $($fields)* } )? $( = $discriminant )?
+            ),*
+        });
 
-    #[test]
-    fn test_enum_variants() {
-        assert_enum_variants!(my_mod::MyEnum, { A, B, C });
-        assert_enum_variants!(Never, {});
+        const _: () = {
+            $(
+                $(#[$attr])*
+                $crate::__enum_variant_entry!(@use [$enum] $head $($variant)?);
+            )*
+
+            let discriminants: &[i128] = &[
+                $(
+                    $(#[$attr])*
+                    ($crate::__enum_variant_entry!(@value $head $($variant)?) as i128)
+                ),*
+            ];
+            let mut i = 0;
+            while i < discriminants.len() {
+                core::assert!(
+                    discriminants[i] == i as i128,
+                    "the variants are not declared in the listed order with implicit discriminants",
+                );
+                i += 1;
+            }
+        };
+    };
+}
+
+/// This macro performs a compile-time check to validate that an enum has *at least*
+/// the variants provided in the macro invocation.
+///
+/// Unlike [`assert_enum_variants!`], it doesn't require the list to be exhaustive,
+/// so it doesn't have to be updated whenever an unrelated variant is added. Shapes,
+/// payload types and discriminants can be asserted just like with
+/// [`assert_enum_variants!`].
+///
+/// It's also the check to use for `#[non_exhaustive]` enums defined in other crates,
+/// on which no `match` can be exhaustive without a wildcard arm. There is no
+/// compile-time mechanism to assert that a list of their variants is complete: the
+/// compiler deliberately doesn't expose the full set of variants of such an enum to
+/// other crates, so variants added in a later version of the dependency go
+/// unnoticed.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::assert_enum_contains;
+///
+/// #[allow(dead_code)]
+/// pub enum Error {
+///     Timeout,
+///     Refused,
+///     Io(std::io::Error),
+/// }
+///
+/// // This will compile successfully
+/// // because `Error` has both the `Timeout` and the `Refused` variants.
+/// assert_enum_contains!(Error, { Timeout, Refused });
+/// ```
+///
+/// # Example of failure due to a missing variant
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_contains;
+///
+/// #[allow(dead_code)]
+/// pub enum Error {
+///     Timeout,
+///     Io(std::io::Error),
+/// }
+///
+/// // This will fail to compile
+/// // because the `Refused` variant is not present on `Error`.
+/// assert_enum_contains!(Error, { Timeout, Refused });
+/// ```
+#[macro_export]
+macro_rules! assert_enum_contains {
+    ($enum:path, {
+        $(
+            $(#[$attr:meta])* $head:ident $($variant:ident)?
+            $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )? $( = $discriminant:expr )?
+        ),* $(,)?
+    }) => {
+        const _: () = {
+            $crate::__assert_enum_variants!($enum, [$enum], [], [_], {
+                $(
+                    $(#[$attr])* $head $($variant)?
+                    $( ( $($tuple)* ) )? $( { $($fields)* } )? $( = $discriminant )?
+                ),*
+            });
+        };
+    };
+}
+
+/// This macro performs a compile-time check to validate that an enum has *none* of
+/// the variants provided in the macro invocation.
+///
+/// It can be used to enforce policies such as "this enum must not have a catch-all
+/// variant".
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::assert_enum_lacks;
+///
+/// #[allow(dead_code)]
+/// pub enum Status {
+///     Active,
+///     Suspended,
+/// }
+///
+/// // This will compile successfully
+/// // because `Status` has neither the `Unknown` nor the `Other` variant.
+/// assert_enum_lacks!(Status, { Unknown, Other });
+/// ```
+///
+/// # Example of failure due to a present variant
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enum_lacks;
+///
+/// #[allow(dead_code)]
+/// pub enum Status {
+///     Active,
+///     Suspended,
+///     Other,
+/// }
+///
+/// // This will fail to compile
+/// // because the `Other` variant is present on `Status`.
+/// assert_enum_lacks!(Status, { Unknown, Other });
+/// ```
+///
+/// The compiler reports the failure as "expected type, found variant `Other`".
+#[macro_export]
+macro_rules! assert_enum_lacks {
+    ($enum:path, { $( $(#[$attr:meta])* $variant:ident ),* $(,)? }) => {
+        const _: () = {
+            // Every listed name resolves to a placeholder type unless the glob import
+            // of the variants brings a variant with the same name into scope.
+            #[allow(dead_code)]
+            mod lacked {
+                $(
+                    $(#[$attr])*
+                    pub struct $variant;
+                )*
+            }
+
+            #[allow(unused_imports)]
+            use $enum::{ * };
+            #[allow(unused_imports)]
+            use lacked::*;
+
+            $(
+                $(#[$attr])*
+                let _: $variant;
+            )*
+        };
+    };
+}
+
+/// This macro performs the same compile-time check as [`assert_enum_variants!`] and
+/// evaluates to the number of variants as a constant `usize`.
+///
+/// Since the expression is constant, it can be used wherever the number of variants
+/// is needed at compile time, such as in array lengths or const generic arguments.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::enum_variant_count;
+///
+/// #[allow(dead_code)]
+/// pub enum Level {
+///     Debug,
+///     Info,
+///     Warn,
+/// }
+///
+/// // This will compile successfully
+/// // because all variants of `Level` are accounted for.
+/// static COUNTERS: [u32; enum_variant_count!(Level, { Debug, Info, Warn })] = [0; 3];
+/// ```
+///
+/// # Example of failure due to missing variants
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::enum_variant_count;
+///
+/// #[allow(dead_code)]
+/// pub enum Level {
+///     Debug,
+///     Info,
+///     Warn,
+///     Error,
+/// }
+///
+/// // This will fail to compile
+/// // because the `Error` variant is missing.
+/// const LEVELS: usize = enum_variant_count!(Level, { Debug, Info, Warn });
+/// ```
+#[macro_export]
+macro_rules! enum_variant_count {
+    ($enum:path, {
+        $(
+            $(#[$attr:meta])* $head:ident $($variant:ident)?
+            $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )? $( = $discriminant:expr )?
+        ),* $(,)?
+    }) => {{
+        $crate::assert_enum_variants!($enum, {
+            $(
+                $(#[$attr])* $head $($variant)?
+                $( ( $($tuple)* ) )? $( { $($fields)* } )? $( = $discriminant )?
+            ),*
+        });
+
+        <[&str]>::len(&[ $( $(#[$attr])* core::stringify!($head) ),* ])
+    }};
+}
+
+/// This macro performs the same compile-time check as [`assert_enum_variants!`] and
+/// evaluates to a constant `[&'static str; N]` array of the listed variant names.
+///
+/// The names are in the order of the macro invocation, so that the array can't
+/// drift apart from the enum. Raw identifiers are named without their `r#` prefix,
+/// as in [`EnumVariants::NAMES`].
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::enum_variant_names;
+///
+/// #[allow(dead_code)]
+/// pub enum Level {
+///     Debug,
+///     Info,
+///     Warn,
+/// }
+///
+/// // This will compile successfully
+/// // because all variants of `Level` are accounted for.
+/// const NAMES: [&str; 3] = enum_variant_names!(Level, { Debug, Info, Warn });
+///
+/// assert_eq!(NAMES, ["Debug", "Info", "Warn"]);
+/// ```
+///
+/// # Example of failure due to missing variants
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::enum_variant_names;
+///
+/// #[allow(dead_code)]
+/// pub enum Level {
+///     Debug,
+///     Info,
+///     Warn,
+///     Error,
+/// }
+///
+/// // This will fail to compile
+/// // because the `Error` variant is missing.
+/// const NAMES: [&str; 3] = enum_variant_names!(Level, { Debug, Info, Warn });
+/// ```
+#[macro_export]
+macro_rules! enum_variant_names {
+    ($enum:path, {
+        $(
+            $(#[$attr:meta])* $head:ident $($variant:ident)?
+            $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )? $( = $discriminant:expr )?
+        ),* $(,)?
+    }) => {{
+        $crate::assert_enum_variants!($enum, {
+            $(
+                $(#[$attr])* $head $($variant)?
+                $( ( $($tuple)* ) )? $( { $($fields)* } )? $( = $discriminant )?
+            ),*
+        });
+
+        [ $( $(#[$attr])* $crate::__enum_variant_entry!(@name $head $($variant)?) ),* ]
+    }};
+}
+
+/// This macro performs a compile-time check to validate that several enums have the
+/// same variant names, e.g. parallel enums in different layers of an application.
+///
+/// The names are compared regardless of their order and of the shapes of the
+/// variants. The enums must implement [`EnumVariants`], e.g. through
+/// `#[derive(EnumVariants)]` with the `derive` feature, and the failure lists every
+/// variant that is missing from or unexpected in each enum compared to the first one.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use assert_enum_variants::assert_enums_mirror;
+///
+/// mod api {
+///     use assert_enum_variants::EnumVariants;
+///
+///     #[allow(dead_code)]
+///     #[derive(EnumVariants)]
+///     pub enum Status {
+///         Active,
+///         Suspended { reason: String },
+///     }
+/// }
+///
+/// mod db {
+///     use assert_enum_variants::EnumVariants;
+///
+///     #[allow(dead_code)]
+///     #[derive(EnumVariants)]
+///     pub enum Status {
+///         Suspended,
+///         Active,
+///     }
+/// }
+///
+/// // This will compile successfully
+/// // because both enums have the `Active` and `Suspended` variants.
+/// assert_enums_mirror!(api::Status, db::Status);
+/// # }
+/// ```
+///
+/// # Example of failure due to a variant missing from one of the enums
+///
+/// If a `Deleted` variant is added to `api::Status` only, the invocation fails to
+/// compile with "the variants of `db::Status` don't mirror those of `api::Status`;
+/// missing: `Deleted`". This failure is exercised in the documentation of the
+/// derive, which requires the `derive` feature.
+///
+/// # Renamed variants
+///
+/// Two enums whose variants correspond one-to-one under different names can be
+/// checked by providing the full correspondence between the variants of the first
+/// enum and those of the second one. Variants with the same name in both enums are
+/// listed once. In this form, each enum is checked against its side of the
+/// correspondence as with [`assert_enum_variants!`], so neither enum needs to
+/// implement [`EnumVariants`].
+///
+/// ```rust
+/// use assert_enum_variants::assert_enums_mirror;
+///
+/// mod v1 {
+///     #[allow(dead_code)]
+///     pub enum Kind {
+///         Pdf,
+///         Docx,
+///     }
+/// }
+///
+/// mod v2 {
+///     #[allow(dead_code)]
+///     pub enum Kind {
+///         PortableDocument,
+///         Docx,
+///     }
+/// }
+///
+/// // This will compile successfully
+/// // because `Pdf` corresponds to `PortableDocument` and `Docx` to `Docx`.
+/// assert_enums_mirror!(v1::Kind, v2::Kind, { Pdf => PortableDocument, Docx });
+/// ```
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::assert_enums_mirror;
+///
+/// mod v1 {
+///     #[allow(dead_code)]
+///     pub enum Kind {
+///         Pdf,
+///         Docx,
+///     }
+/// }
+///
+/// mod v2 {
+///     #[allow(dead_code)]
+///     pub enum Kind {
+///         PortableDocument,
+///         Docx,
+///         Markdown,
+///     }
+/// }
+///
+/// // This will fail to compile
+/// // because `Markdown` has no counterpart in `v1::Kind`.
+/// assert_enums_mirror!(v1::Kind, v2::Kind, { Pdf => PortableDocument, Docx });
+/// ```
+#[macro_export]
+macro_rules! assert_enums_mirror {
+    ($left:path, $right:path, { $($entries:tt)* }) => {
+        $crate::__assert_enums_mirror!($left, $right, [] [] { $($entries)* });
+    };
+    ($first:path, $($other:path),+ $(,)?) => {
+        $(
+            const _: () = $crate::__private::assert_same_names(
+                core::concat!(
+                    "the variants of `",
+                    core::stringify!($other),
+                    "` don't mirror those of `",
+                    core::stringify!($first),
+                    "`",
+                ),
+                <$first as $crate::EnumVariants>::NAMES,
+                <$other as $crate::EnumVariants>::NAMES,
+            );
+        )+
+    };
+}
+
+/// This macro generates the conversions in both directions between two fieldless
+/// enums whose variants correspond one-to-one.
+///
+/// Every variant of both enums has to appear in the mapping exactly once, so that a
+/// variant added to either enum can't be converted silently through a wildcard arm.
+/// Both enums are checked as with [`assert_enum_variants!`], and the generated
+/// `From` implementations match exhaustively on the variants.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::enum_bijection;
+///
+/// mod protocol {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Status {
+///         Up,
+///         Down,
+///     }
+/// }
+///
+/// mod domain {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Status {
+///         Online,
+///         Offline,
+///     }
+/// }
+///
+/// enum_bijection!(protocol::Status, domain::Status, {
+///     Up => Online,
+///     Down => Offline,
+/// });
+///
+/// assert_eq!(domain::Status::from(protocol::Status::Up), domain::Status::Online);
+/// assert_eq!(protocol::Status::from(domain::Status::Offline), protocol::Status::Down);
+/// ```
+///
+/// # Example of failure due to an unmapped variant
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::enum_bijection;
+///
+/// mod protocol {
+///     pub enum Status {
+///         Up,
+///         Down,
+///         Degraded,
+///     }
+/// }
+///
+/// mod domain {
+///     pub enum Status {
+///         Online,
+///         Offline,
+///     }
+/// }
+///
+/// // This will fail to compile
+/// // because `Degraded` is not mapped to any variant of `domain::Status`.
+/// enum_bijection!(protocol::Status, domain::Status, {
+///     Up => Online,
+///     Down => Offline,
+/// });
+/// ```
+#[macro_export]
+macro_rules! enum_bijection {
+    ($left:path, $right:path, {
+        $( $(#[$attr:meta])* $left_variant:ident => $right_variant:ident ),* $(,)?
+    }) => {
+        // Listing the variants as unit variants rejects fields, and listing them
+        // separately for each enum rejects a variant mapped twice.
+        $crate::assert_enum_variants!($left, { $( $(#[$attr])* unit $left_variant ),* });
+        $crate::assert_enum_variants!($right, { $( $(#[$attr])* unit $right_variant ),* });
+
+        impl core::convert::From<$left> for $right {
+            fn from(value: $left) -> Self {
+                match value {
+                    $( $(#[$attr])* <$left>::$left_variant => Self::$right_variant, )*
+                }
+            }
+        }
+
+        impl core::convert::From<$right> for $left {
+            fn from(value: $right) -> Self {
+                match value {
+                    $( $(#[$attr])* <$right>::$right_variant => Self::$left_variant, )*
+                }
+            }
+        }
+    };
+}
+
+/// This macro generates the conversions between an enum and a narrower enum whose
+/// variants correspond to some of the variants of the wider one.
+///
+/// Every variant of the wider enum has to be either mapped to a variant of the
+/// narrower enum or listed as `unmapped`, and every variant of the narrower enum has
+/// to be mapped exactly once. The generated `TryFrom` implementation returns the
+/// value of the wider enum as the error for the unmapped variants, which may have
+/// fields, while the mapped variants of both enums have to be fieldless.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::enum_projection;
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum Error {
+///     Timeout,
+///     Refused,
+///     Io(String),
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum Retryable {
+///     Timeout,
+///     Refused,
+/// }
+///
+/// enum_projection!(Error => Retryable, {
+///     Timeout => Timeout,
+///     Refused => Refused,
+/// }, unmapped { Io });
+///
+/// assert_eq!(Retryable::try_from(Error::Timeout), Ok(Retryable::Timeout));
+/// assert_eq!(
+///     Retryable::try_from(Error::Io("closed".to_owned())),
+///     Err(Error::Io("closed".to_owned())),
+/// );
+/// assert_eq!(Error::from(Retryable::Refused), Error::Refused);
+/// ```
+///
+/// # Example of failure due to a variant neither mapped nor unmapped
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::enum_projection;
+///
+/// pub enum Error {
+///     Timeout,
+///     Refused,
+///     Io(String),
+///     Tls(String),
+/// }
+///
+/// pub enum Retryable {
+///     Timeout,
+///     Refused,
+/// }
+///
+/// // This will fail to compile
+/// // because `Tls` is neither mapped nor listed as unmapped.
+/// enum_projection!(Error => Retryable, {
+///     Timeout => Timeout,
+///     Refused => Refused,
+/// }, unmapped { Io });
+/// ```
+#[macro_export]
+macro_rules! enum_projection {
+    (
+        $wide:path => $narrow:path,
+        { $( $(#[$attr:meta])* $wide_variant:ident => $narrow_variant:ident ),* $(,)? }
+        $(, unmapped { $( $(#[$unmapped_attr:meta])* $unmapped:ident ),* $(,)? })? $(,)?
+    ) => {
+        $crate::assert_enum_variants!($wide, {
+            $( $(#[$attr])* unit $wide_variant, )*
+            $($( $(#[$unmapped_attr])* $unmapped, )*)?
+        });
+        $crate::assert_enum_variants!($narrow, { $( $(#[$attr])* unit $narrow_variant ),* });
+
+        impl core::convert::TryFrom<$wide> for $narrow {
+            type Error = $wide;
+
+            fn try_from(value: $wide) -> core::result::Result<Self, Self::Error> {
+                // The unmapped variants may have fields, which can't be matched through
+                // a qualified path, but the check above guarantees that the wildcard
+                // covers exactly those variants.
+                #[allow(unreachable_patterns)]
+                match value {
+                    $(
+                        $(#[$attr])*
+                        <$wide>::$wide_variant => core::result::Result::Ok(Self::$narrow_variant),
+                    )*
+                    _ => core::result::Result::Err(value),
+                }
+            }
+        }
+
+        impl core::convert::From<$narrow> for $wide {
+            fn from(value: $narrow) -> Self {
+                match value {
+                    $( $(#[$attr])* <$narrow>::$narrow_variant => Self::$wide_variant, )*
+                }
+            }
+        }
+    };
+}
+
+/// This macro maps every variant of a fieldless enum to a string and generates the
+/// conversions between them.
+///
+/// The mapping is checked as with [`assert_enum_variants!`], and mapping several
+/// variants to the same string fails to compile. The macro generates
+///
+/// * `ALL`, a constant table of the variants along with their strings, in the order
+///   of the macro invocation,
+/// * `as_str`, a `const` method returning the string of a variant, and
+/// * an implementation of [`FromStr`](core::str::FromStr) failing with
+///   [`ParseVariantError`] for strings that don't correspond to any variant.
+///
+/// Since `ALL` and `as_str` are inherent items, the macro has to be invoked in the
+/// crate defining the enum.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::variant_strings;
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum ResumeFileFormat {
+///     Pdf,
+///     Docx,
+///     Doc,
+/// }
+///
+/// variant_strings!(ResumeFileFormat {
+///     Pdf => "pdf",
+///     Docx => "docx",
+///     Doc => "doc",
+/// });
+///
+/// assert_eq!(ResumeFileFormat::Docx.as_str(), "docx");
+/// assert_eq!("pdf".parse(), Ok(ResumeFileFormat::Pdf));
+/// assert!("odt".parse::<ResumeFileFormat>().is_err());
+/// assert_eq!(ResumeFileFormat::ALL[2], (ResumeFileFormat::Doc, "doc"));
+/// ```
+///
+/// # Example of failure due to a duplicate string
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::variant_strings;
+///
+/// pub enum ResumeFileFormat {
+///     Pdf,
+///     Docx,
+///     Doc,
+/// }
+///
+/// // This will fail to compile with "several variants are mapped to the same
+/// // string: `doc`".
+/// variant_strings!(ResumeFileFormat {
+///     Pdf => "pdf",
+///     Docx => "doc",
+///     Doc => "doc",
+/// });
+/// ```
+#[macro_export]
+macro_rules! variant_strings {
+    ($enum:path { $( $(#[$attr:meta])* $variant:ident => $string:literal ),* $(,)? }) => {
+        $crate::assert_enum_variants!($enum, { $( $(#[$attr])* unit $variant ),* });
+
+        const _: () = $crate::__private::assert_distinct_strings(&[
+            $( $(#[$attr])* $string ),*
+        ]);
+
+        impl $enum {
+            /// The variants along with their strings.
+            pub const ALL: &'static [(Self, &'static str)] = &[
+                $( $(#[$attr])* (Self::$variant, $string) ),*
+            ];
+
+            /// Returns the string the variant is mapped to.
+            pub const fn as_str(&self) -> &'static str {
+                match self {
+                    $( $(#[$attr])* Self::$variant => $string, )*
+                }
+            }
+        }
+
+        impl core::str::FromStr for $enum {
+            type Err = $crate::ParseVariantError;
+
+            fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+                match s {
+                    $( $(#[$attr])* $string => core::result::Result::Ok(Self::$variant), )*
+                    _ => core::result::Result::Err($crate::ParseVariantError),
+                }
+            }
+        }
+    };
+}
+
+/// This macro performs the same compile-time check as [`assert_enum_variants!`] and
+/// evaluates to a [`VariantTable`] holding a value for every variant, which can be
+/// stored in a constant. Values are looked up at run time.
+///
+/// Unlike a `match` with a catch-all arm returning a default, the table fails to
+/// compile when a variant is missing or listed twice.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::{variant_table, VariantTable};
+///
+/// #[allow(dead_code)]
+/// pub enum Priority {
+///     Low,
+///     Normal,
+///     High { escalated: bool },
+/// }
+///
+/// const WEIGHTS: VariantTable<Priority, u32, 3> = variant_table!(Priority => u32 {
+///     Low => 1,
+///     Normal => 10,
+///     High => 100,
+/// });
+///
+/// assert_eq!(WEIGHTS[Priority::Normal], 10);
+/// assert_eq!(*WEIGHTS.get(&Priority::High { escalated: true }), 100);
+/// assert_eq!(WEIGHTS.values(), &[1, 10, 100]);
+/// ```
+///
+/// # Example of failure due to a missing variant
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::{variant_table, VariantTable};
+///
+/// #[allow(dead_code)]
+/// pub enum Priority {
+///     Low,
+///     Normal,
+///     High,
+/// }
+///
+/// // This will fail to compile
+/// // because the `High` variant is missing.
+/// const WEIGHTS: VariantTable<Priority, u32, 2> = variant_table!(Priority => u32 {
+///     Low => 1,
+///     Normal => 10,
+/// });
+/// ```
+#[macro_export]
+macro_rules! variant_table {
+    ($enum:path => $value:ty { $( $(#[$attr:meta])* $variant:ident => $entry:expr ),* $(,)? }) => {{
+        $crate::assert_enum_variants!($enum, { $( $(#[$attr])* $variant ),* });
+
+        fn position(variant: &$enum) -> usize {
+            // The discriminants of a fieldless enum with the same variants are their
+            // positions in the macro invocation. It's declared in a module so that it
+            // doesn't collide with the imported variants.
+            mod __variant_table {
+                #[allow(dead_code, non_camel_case_types, clippy::enum_variant_names)]
+                pub enum Position {
+                    $( $(#[$attr])* $variant ),*
+                }
+            }
+
+            $(
+                $(#[$attr])*
+                #[allow(unused_imports)]
+                use $enum::{ $variant };
+            )*
+
+            match *variant {
+                $( $(#[$attr])* $variant { .. } => __variant_table::Position::$variant as usize, )*
+            }
+        }
+
+        $crate::VariantTable::<$enum, $value, _>::__new(
+            [ $( $(#[$attr])* $entry ),* ],
+            position,
+        )
+    }};
+}
+
+/// This macro declares a named set of variants of an enum, after checking that the
+/// enum has all of them as with [`assert_enum_contains!`].
+///
+/// The set is declared as a macro with the given name, which expands to a pattern
+/// matching exactly the variants in the set, e.g. for use with [`matches!`]. Like
+/// any `macro_rules!` macro, it can only be used after its declaration, in the same
+/// module or in a child module, unless it's re-exported with `pub(crate) use`. The
+/// path of the enum is resolved where the pattern is used, so a set used outside of
+/// the module declaring it must name the enum by an absolute path, such as
+/// `crate::errors::Error`. Relations between the sets can be asserted with
+/// [`assert_variant_sets!`].
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::variant_set;
+///
+/// #[allow(dead_code)]
+/// pub enum Error {
+///     Timeout,
+///     Refused,
+///     Io(std::io::ErrorKind),
+/// }
+///
+/// variant_set!(RETRYABLE = Error { Timeout, Refused });
+///
+/// assert!(matches!(Error::Timeout, RETRYABLE!()));
+/// assert!(!matches!(Error::Io(std::io::ErrorKind::Other), RETRYABLE!()));
+/// ```
+///
+/// # Example of failure due to a missing variant
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::variant_set;
+///
+/// #[allow(dead_code)]
+/// pub enum Error {
+///     Timeout,
+///     Io(std::io::ErrorKind),
+/// }
+///
+/// // This will fail to compile
+/// // because the `Refused` variant is not present on `Error`.
+/// variant_set!(RETRYABLE = Error { Timeout, Refused });
+/// ```
+#[macro_export]
+macro_rules! variant_set {
+    ($name:ident = $($segment:ident)::+ { $($variant:ident),+ $(,)? }) => {
+        $crate::assert_enum_contains!($($segment)::+, { $($variant),+ });
+        $crate::__variant_set!(($) $name [$($segment)::+] [$($segment)::+ ::] [$($variant),+]);
+    };
+}
+
+/// This macro performs a compile-time check to validate a relation between sets of
+/// variants declared with [`variant_set!`].
+///
+/// The relation is one of
+///
+/// * `disjoint`, asserting that no variant is in more than one of the sets,
+/// * `cover`, asserting that every variant of the enum is in at least one of the
+///   sets, and
+/// * `partition`, asserting both, i.e. that every variant of the enum is in exactly
+///   one of the sets.
+///
+/// All of the sets must be sets of variants of the same enum.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::{assert_variant_sets, variant_set};
+///
+/// #[allow(dead_code)]
+/// pub enum Error {
+///     Timeout,
+///     Refused,
+///     Io(std::io::ErrorKind),
+///     Tls { alert: u8 },
+/// }
+///
+/// variant_set!(RETRYABLE = Error { Timeout, Refused });
+/// variant_set!(FATAL = Error { Io, Tls });
+/// variant_set!(NETWORK = Error { Timeout, Refused, Tls });
+///
+/// assert_variant_sets!(partition { RETRYABLE, FATAL });
+/// assert_variant_sets!(cover { NETWORK, FATAL });
+/// ```
+///
+/// # Example of failure due to overlapping sets
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::{assert_variant_sets, variant_set};
+///
+/// #[allow(dead_code)]
+/// pub enum Error {
+///     Timeout,
+///     Refused,
+///     Io(std::io::ErrorKind),
+/// }
+///
+/// variant_set!(RETRYABLE = Error { Timeout, Refused });
+/// variant_set!(NETWORK = Error { Timeout, Refused, Io });
+///
+/// // This will fail to compile
+/// // because `Timeout` and `Refused` are in both sets.
+/// assert_variant_sets!(disjoint { RETRYABLE, NETWORK });
+/// ```
+///
+/// # Example of failure due to an uncovered variant
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::{assert_variant_sets, variant_set};
+///
+/// #[allow(dead_code)]
+/// pub enum Error {
+///     Timeout,
+///     Refused,
+///     Io(std::io::ErrorKind),
+/// }
+///
+/// variant_set!(RETRYABLE = Error { Timeout, Refused });
+///
+/// // This will fail to compile
+/// // because `Io` is in none of the sets.
+/// assert_variant_sets!(cover { RETRYABLE });
+/// ```
+///
+/// # Example of failure due to sets of different enums
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::{assert_variant_sets, variant_set};
+///
+/// #[allow(dead_code)]
+/// pub enum Error {
+///     Timeout,
+///     Refused,
+/// }
+///
+/// #[allow(dead_code)]
+/// pub enum Warning {
+///     Timeout,
+///     Refused,
+/// }
+///
+/// variant_set!(ERRORS = Error { Timeout });
+/// variant_set!(WARNINGS = Warning { Refused });
+///
+/// // This will fail to compile
+/// // because the sets are of different enums.
+/// assert_variant_sets!(partition { ERRORS, WARNINGS });
+/// ```
+#[macro_export]
+macro_rules! assert_variant_sets {
+    ($relation:ident { $($set:ident),+ $(,)? }) => {
+        $crate::__assert_variant_sets!(@collect $relation [] [$($set),+]);
+    };
+}
+
+/// This macro declares groups of variants of an enum as with [`variant_set!`], after
+/// checking that every variant of the enum is in exactly one of the groups.
+///
+/// Since the groups partition the enum, a `match` whose arms are the patterns of all
+/// of the groups is exhaustive without a wildcard arm, so adding a variant to the
+/// enum fails to compile until it's assigned to a group, and the `match` expressions
+/// pick it up from there.
+///
+/// As with [`variant_set!`], the path of the enum is resolved where the patterns are
+/// used, so groups used outside of the module declaring them must name the enum by
+/// an absolute path, such as `crate::ops::Op`.
+///
+/// # Example
+///
+/// ```rust
+/// use assert_enum_variants::variant_partition;
+///
+/// #[allow(dead_code)]
+/// pub enum Op {
+///     Get(String),
+///     List,
+///     Put(String, Vec<u8>),
+///     Delete(String),
+/// }
+///
+/// variant_partition!(Op {
+///     read_ops { Get, List },
+///     write_ops { Put, Delete },
+/// });
+///
+/// fn is_read_only(op: &Op) -> bool {
+///     match op {
+///         read_ops!() => true,
+///         write_ops!() => false,
+///     }
+/// }
+///
+/// assert!(is_read_only(&Op::List));
+/// assert!(!is_read_only(&Op::Delete("key".to_owned())));
+/// ```
+///
+/// # Example of failure due to an unassigned variant
+///
+/// ```rust,compile_fail
+/// use assert_enum_variants::variant_partition;
+///
+/// #[allow(dead_code)]
+/// pub enum Op {
+///     Get(String),
+///     List,
+///     Put(String, Vec<u8>),
+///     Delete(String),
+///     Scan,
+/// }
+///
+/// // This will fail to compile
+/// // because `Scan` is in none of the groups.
+/// variant_partition!(Op {
+///     read_ops { Get, List },
+///     write_ops { Put, Delete },
+/// });
+/// ```
+#[macro_export]
+macro_rules! variant_partition {
+    ($($segment:ident)::+ { $($groups:tt)* }) => {
+        $crate::__variant_partition!([$($segment)::+] [] { $($groups)* });
+    };
+}
+
+/// Implementation detail of [`variant_partition!`].
+///
+/// Munches the groups, declaring the set of each, and asserts that the sets partition
+/// the enum once all of them are declared.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __variant_partition {
+    (
+        [$($path:tt)*] [$($name:ident)*]
+        { $group:ident { $($variant:ident),+ $(,)? } $(, $($rest:tt)*)? }
+    ) => {
+        $crate::variant_set!($group = $($path)* { $($variant),+ });
+        $crate::__variant_partition!([$($path)*] [$($name)* $group] { $($($rest)*)? });
+    };
+    ([$($path:tt)*] [$($name:ident)+] {}) => {
+        $crate::assert_variant_sets!(partition { $($name),+ });
+    };
+}
+
+/// Implementation detail of [`variant_set!`].
+///
+/// Declares the macro of the set. A `$` token is passed as `$d` so that the declared
+/// macro can have metavariables of its own.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __variant_set {
+    (($d:tt) $name:ident $path:tt $prefix:tt [$($variant:ident),+]) => {
+        #[allow(unused_macros)]
+        macro_rules! $name {
+            () => {
+                $( $crate::__enum_variant_entry!(@pat $prefix $variant) )|+
+            };
+            (@collect $d relation:tt [$d($d sets:tt)*] [$d($d rest:ident),*]) => {
+                $crate::__assert_variant_sets!(
+                    @collect $d relation
+                    [$d($d sets)* [$path $($variant),+]]
+                    [$d($d rest),*]
+                );
+            };
+        }
+    };
+}
+
+/// Implementation detail of [`assert_variant_sets!`].
+///
+/// Collects the variants of every set by invoking the macros of the sets in turn,
+/// each of which calls back with its enum and variants appended.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_variant_sets {
+    (@collect $relation:tt [$($sets:tt)*] [$set:ident $(, $rest:ident)*]) => {
+        $set!(@collect $relation [$($sets)*] [$($rest),*]);
+    };
+    // The sets are compared as sets of variants of the first enum, once every other
+    // enum is checked to be the same type, whatever the path it's spelled with.
+    (
+        @collect $relation:ident
+        [[$first:tt $($variant:ident),+] $([$other:tt $($other_variant:ident),+])*] []
+    ) => {
+        $( $crate::__assert_variant_sets!(@same $first $other); )*
+        $crate::__assert_variant_sets!(
+            @$relation $first [$($variant,)+ $($($other_variant,)+)*]
+        );
+    };
+    (@same [$($first:tt)*] [$($other:tt)*]) => {
+        const _: core::marker::PhantomData<$($first)*> =
+            core::marker::PhantomData::<$($other)*>;
+    };
+    // Importing the variants of all of the sets into a single scope fails when a
+    // variant is in more than one of them.
+    (@disjoint [$($path:tt)*] [$($variant:ident,)+]) => {
+        const _: () = {
+            #[allow(unused_imports)]
+            use $($path)*::{ $($variant),+ };
+        };
+    };
+    // The variants are referred to through the path of the enum rather than imported,
+    // so that a variant in several sets is accepted.
+    (@cover [$($path:tt)*] [$($variant:ident,)+]) => {
+        const _: () = {
+            $crate::__assert_enum_variants!($($path)*, [], [$($path)*::], [], {
+                $($variant,)+
+            });
+        };
+    };
+    (@partition [$($path:tt)*] [$($variant:ident,)+]) => {
+        $crate::assert_enum_variants!($($path)*, { $($variant,)+ });
+    };
+}
+
+/// Implementation detail of [`assert_enums_mirror!`].
+///
+/// Munches the entries of the rename map, collecting the variant names of both
+/// enums, and asserts each enum against its own list.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_enums_mirror {
+    (
+        $left:path, $right:path, [$($left_name:ident,)*] [$($right_name:ident,)*]
+        { $name:ident => $renamed:ident $(, $($rest:tt)*)? }
+    ) => {
+        $crate::__assert_enums_mirror!(
+            $left, $right, [$($left_name,)* $name,] [$($right_name,)* $renamed,]
+            { $($($rest)*)? }
+        );
+    };
+    (
+        $left:path, $right:path, [$($left_name:ident,)*] [$($right_name:ident,)*]
+        { $name:ident $(, $($rest:tt)*)? }
+    ) => {
+        $crate::__assert_enums_mirror!(
+            $left, $right, [$($left_name,)* $name,] [$($right_name,)* $name,]
+            { $($($rest)*)? }
+        );
+    };
+    ($left:path, $right:path, [$($left_name:ident,)*] [$($right_name:ident,)*] {}) => {
+        $crate::assert_enum_variants!($left, { $($left_name),* });
+        $crate::assert_enum_variants!($right, { $($right_name),* });
+    };
+}
+
+/// Implementation detail of [`assert_enum_variants!`] and [`assert_enum_contains!`].
+///
+/// Expands to the statements performing the checks for the enum type `$enum`. The
+/// variants are either imported from the path in `$path` or, if it's empty, referred
+/// to through `$prefix` (e.g. `Self::`). The optional `$wildcard` pattern makes the
+/// variant list non-exhaustive.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_enum_variants {
+    ($enum:ty, $path:tt, $prefix:tt, [$($wildcard:pat)?], {
+        $(
+            $(#[$attr:meta])* $head:ident $($variant:ident)?
+            $( ( $($tuple:tt)* ) )? $( { $($fields:tt)* } )? $( = $discriminant:expr )?
+        ),* $(,)?
+    }) => {
+        $(
+            $(#[$attr])*
+            $crate::__enum_variant_entry!(@use $path $head $($variant)?);
+        )*
+
+        #[allow(unreachable_code)]
+        if false {
+            #[allow(clippy::diverging_sub_expression)]
+            let _unreachable_obj: $enum = core::unreachable!();
+
+            $(
+                $(#[$attr])*
+                $crate::__enum_variant_entry!(
+                    @check [$enum] $prefix
+                    $head $($variant)? $( ( $($tuple)* ) )? $( { $($fields)* } )?
+                );
+            )*
+
+            #[allow(clippy::unneeded_wildcard_pattern, unreachable_patterns)]
+            match _unreachable_obj {
+                $(
+                    $(#[$attr])*
+                    $crate::__enum_variant_entry!(
+                        @pat $prefix
+                        $head $($variant)? $( ( $($tuple)* ) )? $( { $($fields)* } )?
+                    ) => (),
+                )*
+                $( $wildcard => (), )?
+            };
+        }
+
+        $(
+            $(#[$attr])*
+            $crate::__enum_variant_entry!(
+                @discriminant $prefix $head $($variant)? $( = $discriminant )?
+            );
+        )*
+    };
+}
+
+/// Implementation detail of [`assert_enum_variants!`] and the macros built on it.
+///
+/// Every entry of the variant list is forwarded here as-is, so that the optional
+/// `unit` marker and the shape of the variant can be dispatched on without making
+/// the grammar of the public macro ambiguous.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __enum_variant_entry {
+    (@use [$enum:path] unit $variant:ident) => {
+        #[allow(unused_imports)]
+        use $enum::{ $variant };
+    };
+    (@use [$enum:path] $variant:ident) => {
+        #[allow(unused_imports)]
+        use $enum::{ $variant };
+    };
+    (@use [] $($entry:tt)*) => {};
+
+    (@pat [$($prefix:tt)*] unit $variant:ident) => { $($prefix)* $variant };
+    (@pat [$($prefix:tt)*] $variant:ident) => { $($prefix)* $variant { .. } };
+    (@pat [$($prefix:tt)*] $variant:ident ( $($field:ty),* $(,)? )) => {
+        $($prefix)* $variant ( $($crate::__enum_variant_entry!(@wild $field)),* )
+    };
+    (@pat [$($prefix:tt)*] $variant:ident ( $($field:ty,)* .. )) => {
+        $($prefix)* $variant ( $($crate::__enum_variant_entry!(@wild $field),)* .. )
+    };
+    (@pat [$($prefix:tt)*] $variant:ident { $($field:ident $(: $field_ty:ty)?),* $(,)? }) => {
+        $($prefix)* $variant { .. }
+    };
+    (@pat [$($prefix:tt)*] $variant:ident { $($field:ident $(: $field_ty:ty)?,)* .. }) => {
+        $($prefix)* $variant { $($field: _,)* .. }
+    };
+
+    (@wild $field:ty) => { _ };
+
+    (@value unit $variant:ident) => { $variant };
+    (@value $variant:ident) => { $variant };
+
+    (@name unit $variant:ident) => { $crate::__private::unraw(core::stringify!($variant)) };
+    (@name $variant:ident) => { $crate::__private::unraw(core::stringify!($variant)) };
+
+    // Unlike the rest of the checks, this one is evaluated, so it has to stay outside
+    // of the `if false` block. Casting both sides to `i128` sidesteps the need to know
+    // the `repr` of the enum.
+    (@discriminant [$($prefix:tt)*] unit $variant:ident = $discriminant:expr) => {
+        $crate::__enum_variant_entry!(@discriminant [$($prefix)*] $variant = $discriminant);
+    };
+    (@discriminant [$($prefix:tt)*] $variant:ident = $discriminant:expr) => {
+        core::assert!(
+            $($prefix)* $variant as i128 == ($discriminant) as i128,
+            core::concat!(
+                "the discriminant of `",
+                core::stringify!($variant),
+                "` is not `",
+                core::stringify!($discriminant),
+                "`",
+            ),
+        );
+    };
+    (@discriminant [$($prefix:tt)*] $($entry:tt)*) => {};
+
+    // An identifier pattern silently becomes a binding when the name is not a unit
+    // variant, so the variant is also required to be usable as a value.
+    (@check [$enum:ty] [$($prefix:tt)*] unit $variant:ident) => {
+        let _: $enum = $($prefix)* $variant;
+    };
+    (@check [$enum:ty] [$($prefix:tt)*] $variant:ident ( $($field:ty),* $(,)? )) => {
+        $crate::__enum_variant_entry!(@tuple [$enum] [$($prefix)*] $variant [] [$($field,)*]);
+    };
+    (@check [$enum:ty] [$($prefix:tt)*] $variant:ident ( $($field:ty,)* .. )) => {
+        $crate::__enum_variant_entry!(@tuple [$enum] [$($prefix)*] $variant [] [$($field,)*] ..);
+    };
+    // A struct pattern without `..` would also reject missing fields, but an
+    // initializer reports them by name, which makes for a much clearer error.
+    (
+        @check [$enum:ty] [$($prefix:tt)*]
+        $variant:ident { $($field:ident $(: $field_ty:ty)?),* $(,)? }
+    ) => {
+        #[allow(clippy::diverging_sub_expression)]
+        let _: $enum = $($prefix)* $variant { $($field: core::unreachable!()),* };
+        $crate::__enum_variant_entry!(
+            @struct [$enum] [$($prefix)*] $variant [$($field $(: $field_ty)?,)*]
+        );
+    };
+    (
+        @check [$enum:ty] [$($prefix:tt)*]
+        $variant:ident { $($field:ident $(: $field_ty:ty)?,)* .. }
+    ) => {
+        $crate::__enum_variant_entry!(
+            @struct [$enum] [$($prefix)*] $variant [$($field $(: $field_ty)?,)*]
+        );
+    };
+    (@check [$enum:ty] [$($prefix:tt)*] $($entry:tt)*) => {};
+
+    // Positional fields have no names to bind them to, so one binding is introduced
+    // per recursion step, relying on hygiene to keep the bindings apart.
+    (
+        @tuple [$enum:ty] [$($prefix:tt)*] $variant:ident
+        [$($binding:ident: $bound:ty,)*] [$field:ty, $($rest:ty,)*] $($dots:tt)?
+    ) => {
+        $crate::__enum_variant_entry!(
+            @tuple [$enum] [$($prefix)*] $variant
+            [$($binding: $bound,)* field: $field,] [$($rest,)*] $($dots)?
+        );
+    };
+    (
+        @tuple [$enum:ty] [$($prefix:tt)*] $variant:ident
+        [$($binding:ident: $bound:ty,)*] [] $($dots:tt)?
+    ) => {
+        #[allow(clippy::diverging_sub_expression)]
+        let value: $enum = core::unreachable!();
+        #[allow(irrefutable_let_patterns)]
+        if let $($prefix)* $variant($($binding,)* $($dots)?) = value {
+            // The `let` points mismatches at the listed type, while the markers also
+            // reject the types that the `let` would accept through a coercion.
+            $(
+                let _: $bound = $binding;
+                let field_type = $crate::__private::type_of(&$binding);
+                let _: core::marker::PhantomData<$bound> = field_type;
+            )*
+        }
+    };
+
+    // Struct variants live only in the type namespace, so a `let` binding with the
+    // same name is accepted for them and rejected for unit and tuple variants. This
+    // requires the variant to be imported, so it's skipped for prefixed variants.
+    (@struct [$enum:ty] [] $variant:ident [$($field:ident $(: $field_ty:ty)?,)*]) => {
+        {
+            #[allow(non_snake_case, unused_variables)]
+            let $variant = ();
+        }
+        $crate::__enum_variant_entry!(@fields [$enum] [] $variant [$($field $(: $field_ty)?,)*]);
+    };
+    (@struct [$enum:ty] [$($prefix:tt)+] $variant:ident [$($fields:tt)*]) => {
+        $crate::__enum_variant_entry!(@fields [$enum] [$($prefix)+] $variant [$($fields)*]);
+    };
+
+    (@fields [$enum:ty] [$($prefix:tt)*] $variant:ident [$($field:ident $(: $field_ty:ty)?,)*]) => {
+        #[allow(clippy::diverging_sub_expression)]
+        let value: $enum = core::unreachable!();
+        #[allow(irrefutable_let_patterns, unused_variables)]
+        if let $($prefix)* $variant { $($field,)* .. } = value {
+            $($(
+                let _: $field_ty = $field;
+                let field_type = $crate::__private::type_of(&$field);
+                let _: core::marker::PhantomData<$field_ty> = field_type;
+            )?)*
+        }
+    };
+}
+
+/// The shape of a variant of an enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VariantKind {
+    /// A variant without fields, e.g. `A`.
+    Unit,
+    /// A variant with positional fields, e.g. `B(u32)`.
+    Tuple,
+    /// A variant with named fields, e.g. `C { a: u64 }`.
+    Struct,
+}
+
+/// A trait exposing the variants of an enum in the order of declaration.
+///
+/// It can be implemented with `#[derive(EnumVariants)]` when the `derive` feature is
+/// enabled. The declarative macros of this crate don't require it, but
+/// `assert_enum_variants!(Enum, derived { ... })` uses it to report every mismatching
+/// variant at once.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use assert_enum_variants::{EnumVariants, VariantKind};
+///
+/// #[allow(dead_code)]
+/// #[derive(EnumVariants)]
+/// pub enum Shape {
+///     Empty,
+///     Circle(f64),
+///     Rect { width: f64, height: f64 },
+/// }
+///
+/// assert_eq!(Shape::COUNT, 3);
+/// assert_eq!(Shape::NAMES, ["Empty", "Circle", "Rect"]);
+/// assert_eq!(
+///     Shape::KINDS,
+///     [VariantKind::Unit, VariantKind::Tuple, VariantKind::Struct],
+/// );
+/// # }
+/// ```
+pub trait EnumVariants {
+    /// The number of variants.
+    const COUNT: usize = Self::NAMES.len();
+    /// The names of the variants.
+    const NAMES: &'static [&'static str];
+    /// The shapes of the variants.
+    const KINDS: &'static [VariantKind];
+}
+
+/// The error returned by the `FromStr` implementations generated by
+/// [`variant_strings!`] for strings that don't correspond to any variant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ParseVariantError;
+
+impl core::fmt::Display for ParseVariantError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("the string doesn't correspond to any variant")
+    }
+}
+
+impl core::error::Error for ParseVariantError {}
+
+/// A table of values keyed by the variants of an enum, created by [`variant_table!`].
+///
+/// The values are stored in the order of the macro invocation and can be looked up
+/// by variant with [`VariantTable::get`] or by indexing. The position of a variant is
+/// found through a function pointer, so lookups can't be made in const contexts,
+/// unlike [`VariantTable::values`].
+pub struct VariantTable<E, V, const N: usize> {
+    values: [V; N],
+    position: fn(&E) -> usize,
+}
+
+impl<E, V, const N: usize> VariantTable<E, V, N> {
+    #[doc(hidden)]
+    pub const fn __new(values: [V; N], position: fn(&E) -> usize) -> Self {
+        VariantTable { values, position }
+    }
+
+    /// Returns the values in the order of the macro invocation.
+    pub const fn values(&self) -> &[V; N] {
+        &self.values
+    }
+
+    /// Returns the value of the variant.
+    pub fn get(&self, variant: &E) -> &V {
+        &self.values[(self.position)(variant)]
+    }
+}
+
+// Implemented by hand since the derives would require `E` to implement the traits.
+impl<E, V: Clone, const N: usize> Clone for VariantTable<E, V, N> {
+    fn clone(&self) -> Self {
+        VariantTable {
+            values: self.values.clone(),
+            position: self.position,
+        }
+    }
+}
+
+impl<E, V: Copy, const N: usize> Copy for VariantTable<E, V, N> {}
+
+impl<E, V: core::fmt::Debug, const N: usize> core::fmt::Debug for VariantTable<E, V, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VariantTable")
+            .field("values", &self.values)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E, V, const N: usize> core::ops::Index<&E> for VariantTable<E, V, N> {
+    type Output = V;
+
+    fn index(&self, variant: &E) -> &V {
+        self.get(variant)
+    }
+}
+
+impl<E, V, const N: usize> core::ops::Index<E> for VariantTable<E, V, N> {
+    type Output = V;
+
+    fn index(&self, variant: E) -> &V {
+        self.get(&variant)
+    }
+}
+
+/// Implementation details of the macros of this crate.
+#[doc(hidden)]
+pub mod __private {
+    const CAPACITY: usize = 512;
+    const ELLIPSIS: &str = ", ...";
+
+    struct Message {
+        bytes: [u8; CAPACITY],
+        len: usize,
+        truncated: bool,
+    }
+
+    impl Message {
+        const fn new() -> Self {
+            Message {
+                bytes: [0; CAPACITY],
+                len: 0,
+                truncated: false,
+            }
+        }
+
+        const fn write(&mut self, s: &str) {
+            let mut i = 0;
+            while i < s.len() {
+                self.bytes[self.len + i] = s.as_bytes()[i];
+                i += 1;
+            }
+            self.len += s.len();
+        }
+
+        // Names are never split, so that the message stays valid UTF-8, and room is
+        // always left for the ellipsis marking the truncation.
+        const fn push_name(&mut self, separator: &str, name: &str) {
+            if self.truncated {
+                return;
+            }
+            if self.len + separator.len() + name.len() + 2 > CAPACITY - ELLIPSIS.len() {
+                self.write(ELLIPSIS);
+                self.truncated = true;
+                return;
+            }
+            self.write(separator);
+            self.write("`");
+            self.write(name);
+            self.write("`");
+        }
+
+        const fn push_names(&mut self, label: &str, names: &[&str], others: &[&str]) {
+            let mut first = true;
+            let mut i = 0;
+            while i < names.len() {
+                if !contains(others, names[i]) {
+                    self.push_name(if first { label } else { ", " }, names[i]);
+                    first = false;
+                }
+                i += 1;
+            }
+        }
+
+        const fn panic(&self, fallback: &str) -> ! {
+            let (bytes, _) = self.bytes.split_at(self.len);
+            match core::str::from_utf8(bytes) {
+                Ok(message) => panic!("{}", message),
+                Err(_) => panic!("{}", fallback),
+            }
+        }
+    }
+
+    /// Strips the `r#` prefix of a raw identifier, so that names are spelled as by
+    /// `#[derive(EnumVariants)]`.
+    pub const fn unraw(name: &str) -> &str {
+        match name.as_bytes() {
+            [b'r', b'#', rest @ ..] => match core::str::from_utf8(rest) {
+                Ok(name) => name,
+                Err(_) => name,
+            },
+            _ => name,
+        }
+    }
+
+    const fn eq(a: &str, b: &str) -> bool {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+        if a.len() != b.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    const fn contains(names: &[&str], name: &str) -> bool {
+        let mut i = 0;
+        while i < names.len() {
+            if eq(names[i], name) {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+
+    /// Returns a marker of the type of its argument. Unlike a `let` with a type
+    /// annotation, comparing markers admits no coercion.
+    pub const fn type_of<T: ?Sized>(_value: &T) -> core::marker::PhantomData<T> {
+        core::marker::PhantomData
+    }
+
+    /// Panics with `header` followed by the lists of missing and unexpected names
+    /// unless `listed` contains exactly the names in `expected`.
+    pub const fn assert_same_names(header: &str, expected: &[&str], listed: &[&str]) {
+        let header = if header.len() > CAPACITY / 2 {
+            "the variants don't match"
+        } else {
+            header
+        };
+        let mut message = Message::new();
+        message.write(header);
+        let header_len = message.len;
+        message.push_names("; missing: ", expected, listed);
+        message.push_names("; unexpected: ", listed, expected);
+        if message.len != header_len {
+            message.panic(header);
+        }
+    }
+
+    /// Panics with the first of `strings` that appears more than once.
+    pub const fn assert_distinct_strings(strings: &[&str]) {
+        let mut i = 0;
+        while i < strings.len() {
+            let mut j = i + 1;
+            while j < strings.len() {
+                if eq(strings[i], strings[j]) {
+                    let header = "several variants are mapped to the same string";
+                    let mut message = Message::new();
+                    message.write(header);
+                    message.push_name(": ", strings[i]);
+                    message.panic(header);
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod my_mod {
+        #[allow(dead_code)]
+        #[cfg_attr(feature = "derive", derive(crate::EnumVariants))]
+        pub enum MyEnum {
+            A,
+            B(u32),
+            C { a: u64, b: u32 },
+        }
+    }
+
+    mod mirror_mod {
+        #[allow(dead_code)]
+        #[cfg_attr(feature = "derive", derive(crate::EnumVariants))]
+        pub enum MyEnum {
+            C,
+            B,
+            A(u8),
+        }
+
+        #[allow(dead_code)]
+        pub enum Renamed {
+            Alpha,
+            B,
+            Gamma { c: u8 },
+        }
+    }
+
+    #[allow(dead_code)]
+    enum Never {}
+
+    #[allow(dead_code)]
+    enum Generic<'a, T> {
+        Borrowed(&'a T),
+        Owned { value: T },
+    }
+
+    #[allow(dead_code)]
+    enum Gated {
+        Always,
+        #[cfg(test)]
+        Test,
+        #[cfg(not(test))]
+        NotTest,
+    }
+
+    #[allow(dead_code)]
+    #[repr(i16)]
+    enum Code {
+        Negative = -2,
+        Ok = 0,
+        NotFound = 404,
+        Next,
+    }
+
+    #[test]
+    fn test_enum_variants() {
+        assert_enum_variants!(my_mod::MyEnum, { A, B, C });
+        assert_enum_variants!(my_mod::MyEnum, { unit A, B(..), C { .. } });
+        assert_enum_variants!(my_mod::MyEnum, { A, B, C { b, a } });
+        assert_enum_variants!(my_mod::MyEnum, { A, B, C { a, .. } });
+        assert_enum_variants!(my_mod::MyEnum, { A, B(_), C });
+        assert_enum_variants!(my_mod::MyEnum, { A, B(_, ..), C });
+        assert_enum_variants!(my_mod::MyEnum, { A, B(u32), C { a: u64, b: u32 } });
+        assert_enum_variants!(my_mod::MyEnum, { A, B(u32, ..), C { b: u32, .. } });
+        assert_enum_variants!(Never, {});
+        assert_enum_variants!(Code, { Negative = -2, Ok = 0, NotFound = 404, Next = 405 });
+        assert_enum_variants!(Code, { Negative, unit Ok = 0, NotFound, Next });
+        assert_enum_variants!(Never, ordered {});
+        #[cfg(feature = "derive")]
+        assert_enum_variants!(my_mod::MyEnum, derived { C { a, b }, unit A, B(u32) });
+        #[cfg(feature = "derive")]
+        assert_enum_variants!(Raw, derived { r#type, Other });
+        assert_enum_variants!(Gated, {
+            Always,
+            #[cfg(test)]
+            unit Test = 1,
+            #[cfg(not(test))]
+            unit NotTest = 1,
+        });
+        assert_enum_variants!(Gated, ordered { Always = 0, #[cfg(test)] Test });
+        assert_enum_variants!(for<'a, T> Generic<'a, T>, { Borrowed, Owned });
+        assert_enum_variants!(for<T, 'a> Generic<'a, T>, { Borrowed(&'a T), Owned { value: T } });
+        assert_enum_variants!(for<T> Generic<'static, T>, { Borrowed(_), Owned { .. } } where T: Copy);
+    }
+
+    impl<T: Clone> Generic<'_, T> {
+        const VARIANTS: () = assert_enum_variants!(Self, { Borrowed(&T), Owned { value: T } });
+
+        fn is_owned(&self) -> bool {
+            assert_enum_variants!(Self, { Borrowed(..), Owned { value } });
+            matches!(self, Self::Owned { .. })
+        }
+    }
+
+    impl my_mod::MyEnum {
+        fn check() {
+            assert_enum_variants!(Self, { unit A, B(u32), C { a: u64, b } });
+        }
+    }
+
+    trait Codec {
+        type Error;
+    }
+
+    impl Codec for Never {
+        type Error = my_mod::MyEnum;
+    }
+
+    impl Codec for Code {
+        type Error = Code;
+    }
+
+    assert_enum_variants!(<Never as Codec>::Error, { unit A, B(u32), C { a: u64, b: u32 } });
+    assert_enum_variants!(<Code as Codec>::Error, { Negative = -2, Ok, NotFound = 404, Next });
+    assert_enum_variants!(
+        for<T> <T as Codec>::Error,
+        { A, B(u32), C { a, .. } }
+        where T: Codec<Error = my_mod::MyEnum>
+    );
+    assert_enum_variants!(
+        for<T> T::Error,
+        { A, B(u32), C { a: u64, b: u32 } }
+        where T: Codec<Error = my_mod::MyEnum>
+    );
+
+    #[test]
+    fn test_enum_variants_in_impl() {
+        let value = 1;
+        let generic = Generic::Borrowed(&value);
+
+        assert!(!generic.is_owned());
+        let () = Generic::<'_, u8>::VARIANTS;
+        my_mod::MyEnum::check();
+    }
+
+    #[test]
+    fn test_enum_contains() {
+        assert_enum_contains!(my_mod::MyEnum, { A, C });
+        assert_enum_contains!(my_mod::MyEnum, { B(u32), C { a, .. } });
+        assert_enum_contains!(my_mod::MyEnum, { A, B, C });
+        assert_enum_contains!(Never, {});
+        assert_enum_contains!(Code, { NotFound = 404 });
+        assert_enum_contains!(core::sync::atomic::Ordering, {
+            unit Relaxed,
+            unit Release,
+            unit Acquire,
+            unit AcqRel,
+            unit SeqCst,
+        });
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_enum_variants() {
+        use super::{EnumVariants, VariantKind};
+
+        assert_eq!(my_mod::MyEnum::COUNT, 3);
+        assert_eq!(my_mod::MyEnum::NAMES, ["A", "B", "C"]);
+        assert_eq!(
+            my_mod::MyEnum::KINDS,
+            [VariantKind::Unit, VariantKind::Tuple, VariantKind::Struct]
+        );
+        assert_eq!(mirror_mod::MyEnum::NAMES, ["C", "B", "A"]);
+    }
+
+    #[test]
+    fn test_enums_mirror() {
+        #[cfg(feature = "derive")]
+        assert_enums_mirror!(my_mod::MyEnum, mirror_mod::MyEnum);
+        #[cfg(feature = "derive")]
+        assert_enums_mirror!(mirror_mod::MyEnum, my_mod::MyEnum, mirror_mod::MyEnum,);
+        assert_enums_mirror!(my_mod::MyEnum, mirror_mod::MyEnum, { A, B, C });
+        assert_enums_mirror!(my_mod::MyEnum, mirror_mod::Renamed, { A => Alpha, B, C => Gamma });
+    }
+
+    mod bijection_mod {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub enum Light {
+            Red,
+            Green,
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub enum Signal {
+            Stop,
+            Go,
+        }
+
+        enum_bijection!(Light, Signal, { Red => Stop, Green => Go });
+    }
+
+    #[test]
+    fn test_enum_bijection() {
+        use bijection_mod::{Light, Signal};
+
+        assert_eq!(Signal::from(Light::Red), Signal::Stop);
+        assert_eq!(Signal::from(Light::Green), Signal::Go);
+        assert_eq!(Light::from(Signal::Stop), Light::Red);
+        assert_eq!(Light::from(Signal::Go), Light::Green);
+    }
+
+    mod projection_mod {
+        #[derive(Debug, PartialEq)]
+        pub enum Wide {
+            A,
+            B,
+            C(u8),
+            D { d: u8 },
+        }
+
+        #[derive(Debug, PartialEq)]
+        pub enum Narrow {
+            First,
+            Second,
+        }
+
+        enum_projection!(Wide => Narrow, { A => First, B => Second }, unmapped { C, D });
+    }
+
+    #[test]
+    fn test_enum_projection() {
+        use projection_mod::{Narrow, Wide};
+
+        assert_eq!(Narrow::try_from(Wide::A), Ok(Narrow::First));
+        assert_eq!(Narrow::try_from(Wide::B), Ok(Narrow::Second));
+        assert_eq!(Narrow::try_from(Wide::C(1)), Err(Wide::C(1)));
+        assert_eq!(Narrow::try_from(Wide::D { d: 2 }), Err(Wide::D { d: 2 }));
+        assert_eq!(Wide::from(Narrow::Second), Wide::B);
+    }
+
+    mod strings_mod {
+        #[derive(Debug, PartialEq)]
+        pub enum Format {
+            Pdf,
+            Docx,
+            Never,
+        }
+
+        variant_strings!(Format {
+            Pdf => "pdf",
+            Docx => "docx",
+            Never => "",
+        });
+    }
+
+    #[test]
+    fn test_variant_strings() {
+        use super::ParseVariantError;
+        use strings_mod::Format;
+
+        assert_eq!(Format::Pdf.as_str(), "pdf");
+        assert_eq!(Format::Never.as_str(), "");
+        assert_eq!("docx".parse(), Ok(Format::Docx));
+        assert_eq!("".parse(), Ok(Format::Never));
+        assert_eq!("Pdf".parse::<Format>(), Err(ParseVariantError));
+        assert_eq!(
+            Format::ALL,
+            [
+                (Format::Pdf, "pdf"),
+                (Format::Docx, "docx"),
+                (Format::Never, "")
+            ],
+        );
+    }
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[cfg_attr(feature = "derive", derive(crate::EnumVariants))]
+    enum Raw {
+        r#type,
+        Other,
+    }
+
+    #[allow(dead_code)]
+    enum Field {
+        Position,
+        Velocity,
+    }
+
+    #[test]
+    fn test_variant_table() {
+        use super::VariantTable;
+
+        const TABLE: VariantTable<my_mod::MyEnum, &str, 3> =
+            variant_table!(my_mod::MyEnum => &str { C => "c", A => "a", B => "b" });
+        let codes = variant_table!(Code => u16 {
+            Negative => 0,
+            Ok => 200,
+            NotFound => 404,
+            Next => 405,
+        });
+        let empty: VariantTable<Never, u8, 0> = variant_table!(Never => u8 {});
+        let fields = variant_table!(Field => u32 { Velocity => 2, Position => 1 });
+
+        assert_eq!(TABLE[my_mod::MyEnum::A], "a");
+        assert_eq!(TABLE[&my_mod::MyEnum::B(1)], "b");
+        assert_eq!(*TABLE.get(&my_mod::MyEnum::C { a: 1, b: 2 }), "c");
+        assert_eq!(TABLE.values(), &["c", "a", "b"]);
+        assert_eq!(codes[Code::NotFound], 404);
+        assert!(empty.values().is_empty());
+        assert_eq!(fields[Field::Position], 1);
+        assert_eq!(fields[Field::Velocity], 2);
+
+        let table = TABLE;
+        let copied = table;
+        assert_eq!(table.clone().values(), copied.values());
+    }
+
+    mod sets_mod {
+        #[allow(dead_code)]
+        pub enum Fault {
+            Timeout,
+            Refused,
+            Io(u8),
+            Tls { alert: u8 },
+        }
+
+        variant_set!(RETRYABLE = Fault { Timeout, Refused });
+        variant_set!(FATAL = Fault { Io, Tls });
+        variant_set!(
+            NETWORK = self::Fault {
+                Timeout,
+                Refused,
+                Tls
+            }
+        );
+        variant_set!(IO = super::sets_mod::Fault { Io });
+
+        assert_variant_sets!(partition { RETRYABLE, FATAL });
+        assert_variant_sets!(partition { NETWORK, IO });
+        assert_variant_sets!(disjoint { RETRYABLE, FATAL });
+        assert_variant_sets!(disjoint { NETWORK, IO });
+        assert_variant_sets!(cover { NETWORK, FATAL });
+        assert_variant_sets!(cover {
+            NETWORK,
+            IO,
+            RETRYABLE
+        });
+
+        pub fn is_retryable(fault: &Fault) -> bool {
+            matches!(fault, RETRYABLE!())
+        }
+
+        pub fn is_network(fault: &Fault) -> bool {
+            matches!(fault, NETWORK!())
+        }
+
+        variant_set!(TLS = crate::tests::sets_mod::Fault { Tls });
+
+        pub mod child {
+            pub fn is_tls(fault: &super::Fault) -> bool {
+                matches!(fault, TLS!())
+            }
+        }
+    }
+
+    #[test]
+    fn test_variant_sets() {
+        use sets_mod::Fault;
+
+        assert!(sets_mod::is_retryable(&Fault::Refused));
+        assert!(!sets_mod::is_retryable(&Fault::Io(0)));
+        assert!(sets_mod::is_network(&Fault::Tls { alert: 0 }));
+        assert!(!sets_mod::is_network(&Fault::Io(0)));
+        assert!(sets_mod::child::is_tls(&Fault::Tls { alert: 0 }));
+        assert!(!sets_mod::child::is_tls(&Fault::Timeout));
+    }
+
+    mod partition_mod {
+        #[allow(dead_code)]
+        pub enum Op {
+            Get,
+            List(u8),
+            Put { key: u8 },
+            Delete(u8),
+        }
+
+        variant_partition!(self::Op {
+            read_ops { Get, List },
+            write_ops { Put, Delete },
+        });
+        variant_partition!(Op { all_ops { Delete, Put, List, Get } });
+        variant_partition!(crate::tests::partition_mod::Op {
+            keyed_ops { Put, Delete },
+            other_ops { Get, List },
+        });
+
+        pub mod child {
+            pub fn is_keyed(op: &super::Op) -> bool {
+                match op {
+                    keyed_ops!() => true,
+                    other_ops!() => false,
+                }
+            }
+        }
+
+        pub fn is_read_only(op: &Op) -> bool {
+            match op {
+                read_ops!() => true,
+                write_ops!() => false,
+            }
+        }
+
+        pub fn is_op(op: &Op) -> bool {
+            match op {
+                all_ops!() => true,
+            }
+        }
+    }
+
+    #[test]
+    fn test_variant_partition() {
+        use partition_mod::Op;
+
+        assert!(partition_mod::is_read_only(&Op::Get));
+        assert!(partition_mod::is_read_only(&Op::List(0)));
+        assert!(!partition_mod::is_read_only(&Op::Put { key: 0 }));
+        assert!(!partition_mod::is_read_only(&Op::Delete(0)));
+        assert!(partition_mod::is_op(&Op::Get));
+        assert!(partition_mod::child::is_keyed(&Op::Put { key: 0 }));
+        assert!(!partition_mod::child::is_keyed(&Op::List(0)));
+    }
+
+    #[test]
+    fn test_enum_lacks() {
+        assert_enum_lacks!(my_mod::MyEnum, { D, Other });
+        assert_enum_lacks!(Never, { A });
+        assert_enum_lacks!(Code, {});
+        assert_enum_lacks!(Gated, { #[cfg(not(test))] Test, #[cfg(test)] NotTest });
+    }
+
+    #[test]
+    fn test_enum_variant_count() {
+        const COUNT: usize = enum_variant_count!(my_mod::MyEnum, { unit A, B(u32), C });
+        let table = [0u8; enum_variant_count!(Code, { Negative, Ok, NotFound, Next })];
+
+        assert_eq!(COUNT, 3);
+        assert_eq!(table.len(), 4);
+        assert_eq!(enum_variant_count!(Never, {}), 0);
+        assert_eq!(
+            enum_variant_count!(Gated, { Always, #[cfg(test)] Test, #[cfg(not(test))] NotTest }),
+            2
+        );
+    }
+
+    #[test]
+    fn test_enum_variant_names() {
+        const NAMES: [&str; 3] = enum_variant_names!(my_mod::MyEnum, { unit A, B(u32), C });
+        let empty: [&str; 0] = enum_variant_names!(Never, {});
+
+        assert_eq!(NAMES, ["A", "B", "C"]);
+        assert!(empty.is_empty());
+        assert_eq!(
+            enum_variant_names!(Raw, { r#type, Other }),
+            ["type", "Other"]
+        );
+        assert_eq!(
+            enum_variant_names!(Gated, { Always, #[cfg(test)] Test, #[cfg(not(test))] NotTest }),
+            ["Always", "Test"],
+        );
     }
 }